@@ -0,0 +1,209 @@
+use anyhow::{anyhow, Result};
+use serde::Deserialize;
+use std::{
+    cmp::Ordering,
+    collections::{BTreeMap, BTreeSet},
+    fs,
+    path::Path,
+    rc::Rc,
+};
+use ucan::capability::{Ability, CapabilitySemantics, Scope};
+use url::Url;
+
+/// The resource used for fixtures generated from an ability hierarchy
+pub const HIERARCHY_RESOURCE: &str = "https://example.com/resource";
+
+/// Ability implications loaded from a JSON config. Each key is an ability
+/// and its value lists the abilities it directly implies, for example
+/// `{"crud/update": ["crud/create", "crud/read"]}`. Implication is
+/// reflexive and transitive.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(transparent)]
+pub struct AbilityHierarchy(BTreeMap<String, Vec<String>>);
+
+impl AbilityHierarchy {
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let json = fs::read_to_string(path)?;
+        AbilityHierarchy::from_json(&json)
+    }
+
+    pub fn from_json(json: &str) -> Result<Self> {
+        let hierarchy: AbilityHierarchy = serde_json::from_str(json)?;
+
+        for ability in hierarchy.abilities() {
+            HierarchyAbility::try_from(ability)?;
+        }
+
+        Ok(hierarchy)
+    }
+
+    /// Every ability named in the hierarchy, as an implier or as implied
+    pub fn abilities(&self) -> BTreeSet<String> {
+        self.0
+            .iter()
+            .flat_map(|(ability, implied)| std::iter::once(ability).chain(implied.iter()))
+            .cloned()
+            .collect()
+    }
+
+    pub fn implies(&self, ability: &str, other: &str) -> bool {
+        let mut visited: BTreeSet<&str> = BTreeSet::new();
+        let mut pending: Vec<&str> = vec![ability];
+
+        while let Some(current) = pending.pop() {
+            if current == other {
+                return true;
+            }
+
+            if visited.insert(current) {
+                if let Some(implied) = self.0.get(current) {
+                    pending.extend(implied.iter().map(String::as_str));
+                }
+            }
+        }
+
+        false
+    }
+
+    /// Pairs of distinct abilities where the first implies the second
+    pub fn implications(&self) -> Vec<(String, String)> {
+        let abilities = self.abilities();
+
+        abilities
+            .iter()
+            .flat_map(|ability| {
+                abilities
+                    .iter()
+                    .filter(move |other| *other != ability && self.implies(ability, other))
+                    .map(move |other| (ability.clone(), other.clone()))
+            })
+            .collect()
+    }
+
+    /// Pairs of abilities where the first does not imply the second, but
+    /// the second implies the first. Claiming the second from a proof of
+    /// the first escalates.
+    pub fn escalations(&self) -> Vec<(String, String)> {
+        self.implications()
+            .into_iter()
+            .filter(|(ability, implied)| !self.implies(implied, ability))
+            .map(|(ability, implied)| (implied, ability))
+            .collect()
+    }
+
+    pub fn semantics(&self) -> HierarchySemantics {
+        HierarchySemantics {
+            hierarchy: Rc::new(self.clone()),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct HierarchyResource(Url);
+
+impl Scope for HierarchyResource {
+    fn contains(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl ToString for HierarchyResource {
+    fn to_string(&self) -> String {
+        self.0.to_string()
+    }
+}
+
+impl TryFrom<Url> for HierarchyResource {
+    type Error = anyhow::Error;
+
+    fn try_from(value: Url) -> Result<Self> {
+        Ok(HierarchyResource(value))
+    }
+}
+
+/// An ability from a configured hierarchy, carrying the hierarchy it was
+/// parsed under.
+///
+/// `ucan` checks that one ability grants another with `>=`, so the partial
+/// ordering is implication under that hierarchy, and abilities neither
+/// implies are incomparable. The total ordering, needed to satisfy
+/// `Ability`, is lexical. An ability parsed outside `HierarchySemantics`
+/// has an empty hierarchy and implies only itself.
+#[derive(Clone, Debug)]
+pub struct HierarchyAbility {
+    name: String,
+    hierarchy: Rc<AbilityHierarchy>,
+}
+
+impl PartialEq for HierarchyAbility {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+    }
+}
+
+impl Eq for HierarchyAbility {}
+
+#[allow(clippy::non_canonical_partial_ord_impl)]
+impl PartialOrd for HierarchyAbility {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        if self == other {
+            Some(Ordering::Equal)
+        } else if self.hierarchy.implies(&self.name, &other.name) {
+            Some(Ordering::Greater)
+        } else if self.hierarchy.implies(&other.name, &self.name) {
+            Some(Ordering::Less)
+        } else {
+            None
+        }
+    }
+}
+
+impl Ord for HierarchyAbility {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.name.cmp(&other.name)
+    }
+}
+
+impl Ability for HierarchyAbility {}
+
+impl ToString for HierarchyAbility {
+    fn to_string(&self) -> String {
+        self.name.clone()
+    }
+}
+
+impl TryFrom<String> for HierarchyAbility {
+    type Error = anyhow::Error;
+
+    fn try_from(value: String) -> Result<Self> {
+        match value.split_once('/') {
+            Some((namespace, segment)) if !namespace.is_empty() && !segment.is_empty() => {
+                Ok(HierarchyAbility {
+                    name: value,
+                    hierarchy: Rc::default(),
+                })
+            }
+            _ => Err(anyhow!("Ability is not namespaced: {}", value)),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct HierarchySemantics {
+    hierarchy: Rc<AbilityHierarchy>,
+}
+
+impl CapabilitySemantics<HierarchyResource, HierarchyAbility> for HierarchySemantics {
+    fn parse_action(&self, ability: &str) -> Option<HierarchyAbility> {
+        if self.hierarchy.abilities().contains(ability) {
+            HierarchyAbility::try_from(String::from(ability))
+                .ok()
+                .map(|ability| HierarchyAbility {
+                    hierarchy: self.hierarchy.clone(),
+                    ..ability
+                })
+        } else {
+            None
+        }
+    }
+}
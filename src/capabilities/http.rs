@@ -0,0 +1,110 @@
+use anyhow::{anyhow, Result};
+use std::cmp::Ordering;
+use ucan::capability::{Ability, CapabilitySemantics, Scope};
+use url::Url;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct HttpResource(Url);
+
+impl Scope for HttpResource {
+    fn contains(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl ToString for HttpResource {
+    fn to_string(&self) -> String {
+        self.0.to_string()
+    }
+}
+
+impl TryFrom<Url> for HttpResource {
+    type Error = anyhow::Error;
+
+    fn try_from(value: Url) -> Result<Self> {
+        match value.scheme() {
+            "http" | "https" => Ok(HttpResource(value)),
+            _ => Err(anyhow!(
+                "Could not interpret URI as an HTTP resource: {}",
+                value
+            )),
+        }
+    }
+}
+
+/// An HTTP method ability. `http/*` subsumes every method, and methods do
+/// not subsume each other.
+///
+/// `ucan` checks that one ability grants another with `>=`, so the partial
+/// ordering is subsumption, and two different methods are incomparable.
+/// The total ordering, needed to satisfy `Ability`, is declaration order.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum HttpAction {
+    Get,
+    Post,
+    Put,
+    Delete,
+    Any,
+}
+
+impl HttpAction {
+    fn subsumes(&self, other: &Self) -> bool {
+        *self == HttpAction::Any || self == other
+    }
+}
+
+#[allow(clippy::non_canonical_partial_ord_impl)]
+impl PartialOrd for HttpAction {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        if self == other {
+            Some(Ordering::Equal)
+        } else if self.subsumes(other) {
+            Some(Ordering::Greater)
+        } else if other.subsumes(self) {
+            Some(Ordering::Less)
+        } else {
+            None
+        }
+    }
+}
+
+impl Ord for HttpAction {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (*self as u8).cmp(&(*other as u8))
+    }
+}
+
+impl Ability for HttpAction {}
+
+impl ToString for HttpAction {
+    fn to_string(&self) -> String {
+        match self {
+            HttpAction::Get => "http/GET",
+            HttpAction::Post => "http/POST",
+            HttpAction::Put => "http/PUT",
+            HttpAction::Delete => "http/DELETE",
+            HttpAction::Any => "http/*",
+        }
+        .into()
+    }
+}
+
+impl TryFrom<String> for HttpAction {
+    type Error = anyhow::Error;
+
+    fn try_from(value: String) -> Result<Self> {
+        match value.as_str() {
+            "http/GET" => Ok(HttpAction::Get),
+            "http/POST" => Ok(HttpAction::Post),
+            "http/PUT" => Ok(HttpAction::Put),
+            "http/DELETE" => Ok(HttpAction::Delete),
+            "http/*" => Ok(HttpAction::Any),
+            _ => Err(anyhow!("Unrecognized action: {}", value)),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct HttpSemantics {}
+
+impl CapabilitySemantics<HttpResource, HttpAction> for HttpSemantics {}
@@ -1,3 +1,13 @@
 mod email;
+mod hierarchy;
+mod http;
+mod path;
+mod segment;
+mod wnfs;
 
 pub use email::*;
+pub use hierarchy::*;
+pub use http::*;
+pub use path::*;
+pub use segment::*;
+pub use wnfs::*;
@@ -0,0 +1,75 @@
+use anyhow::{anyhow, Result};
+use ucan::capability::{Ability, CapabilitySemantics, Scope};
+use url::Url;
+
+/// An http(s) resource compared path segment by path segment. A resource
+/// contains only itself: `https://example.com/a` does not contain
+/// `https://example.com/a/b`. The spec leaves resource hierarchy to each
+/// semantics, so fixtures take the conservative reading that a sub-path
+/// must be delegated explicitly. A trailing slash is not a segment.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PathResource(Url);
+
+impl PathResource {
+    fn segments(&self) -> Vec<&str> {
+        self.0
+            .path_segments()
+            .map(|segments| segments.filter(|segment| !segment.is_empty()).collect())
+            .unwrap_or_default()
+    }
+}
+
+impl Scope for PathResource {
+    fn contains(&self, other: &Self) -> bool {
+        self.0.origin() == other.0.origin() && self.segments() == other.segments()
+    }
+}
+
+impl ToString for PathResource {
+    fn to_string(&self) -> String {
+        self.0.to_string()
+    }
+}
+
+impl TryFrom<Url> for PathResource {
+    type Error = anyhow::Error;
+
+    fn try_from(value: Url) -> Result<Self> {
+        match value.scheme() {
+            "http" | "https" => Ok(PathResource(value)),
+            _ => Err(anyhow!("Could not interpret URI as a path: {}", value)),
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Debug)]
+pub enum PathAction {
+    Read,
+}
+
+impl Ability for PathAction {}
+
+impl ToString for PathAction {
+    fn to_string(&self) -> String {
+        match self {
+            PathAction::Read => "crud/read",
+        }
+        .into()
+    }
+}
+
+impl TryFrom<String> for PathAction {
+    type Error = anyhow::Error;
+
+    fn try_from(value: String) -> Result<Self> {
+        match value.as_str() {
+            "crud/read" => Ok(PathAction::Read),
+            _ => Err(anyhow!("Unrecognized action: {}", value)),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct PathSemantics {}
+
+impl CapabilitySemantics<PathResource, PathAction> for PathSemantics {}
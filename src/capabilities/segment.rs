@@ -0,0 +1,103 @@
+use anyhow::{anyhow, Result};
+use std::cmp::Ordering;
+use ucan::capability::{Ability, CapabilitySemantics, Scope};
+use url::Url;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SegmentResource(Url);
+
+impl Scope for SegmentResource {
+    fn contains(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl ToString for SegmentResource {
+    fn to_string(&self) -> String {
+        self.0.to_string()
+    }
+}
+
+impl TryFrom<Url> for SegmentResource {
+    type Error = anyhow::Error;
+
+    fn try_from(value: Url) -> Result<Self> {
+        Ok(SegmentResource(value))
+    }
+}
+
+/// An ability of two or more segments, like `crud/file/read`. A final `*`
+/// segment subsumes any segments in its place, so `crud/file/*` subsumes
+/// `crud/file/read`.
+///
+/// `ucan` checks that one ability grants another with `>=`, so the partial
+/// ordering is subsumption, and abilities neither subsumes, like
+/// `crud/file/read` and `crud/dir/read`, are incomparable. The total
+/// ordering, needed to satisfy `Ability`, is lexical.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct SegmentAbility(Vec<String>);
+
+impl SegmentAbility {
+    fn subsumes(&self, other: &Self) -> bool {
+        match self.0.split_last() {
+            Some((last, prefix)) if last == "*" => {
+                other.0.len() > prefix.len() && other.0.starts_with(prefix)
+            }
+            _ => self == other,
+        }
+    }
+}
+
+#[allow(clippy::non_canonical_partial_ord_impl)]
+impl PartialOrd for SegmentAbility {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        if self == other {
+            Some(Ordering::Equal)
+        } else if self.subsumes(other) {
+            Some(Ordering::Greater)
+        } else if other.subsumes(self) {
+            Some(Ordering::Less)
+        } else {
+            None
+        }
+    }
+}
+
+impl Ord for SegmentAbility {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.cmp(&other.0)
+    }
+}
+
+impl Ability for SegmentAbility {}
+
+impl ToString for SegmentAbility {
+    fn to_string(&self) -> String {
+        self.0.join("/")
+    }
+}
+
+impl TryFrom<String> for SegmentAbility {
+    type Error = anyhow::Error;
+
+    fn try_from(value: String) -> Result<Self> {
+        let segments: Vec<String> = value.split('/').map(String::from).collect();
+
+        let well_formed = segments.len() >= 2
+            && segments.iter().all(|segment| !segment.is_empty())
+            && segments[..segments.len() - 1]
+                .iter()
+                .all(|segment| segment != "*");
+
+        if well_formed {
+            Ok(SegmentAbility(segments))
+        } else {
+            Err(anyhow!("Ability is not well formed: {}", value))
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct SegmentSemantics {}
+
+impl CapabilitySemantics<SegmentResource, SegmentAbility> for SegmentSemantics {}
@@ -0,0 +1,81 @@
+use anyhow::{anyhow, Result};
+use ucan::capability::{Ability, CapabilitySemantics, Scope};
+use url::Url;
+
+/// A `wnfs://` file system path. A path contains itself and every path
+/// beneath it, so a capability attenuates by narrowing to a sub-path.
+#[derive(Clone, Debug, PartialEq)]
+pub struct WnfsResource {
+    origin: String,
+    path: Vec<String>,
+}
+
+impl Scope for WnfsResource {
+    fn contains(&self, other: &Self) -> bool {
+        self.origin == other.origin && other.path.starts_with(&self.path)
+    }
+}
+
+impl ToString for WnfsResource {
+    fn to_string(&self) -> String {
+        format!("wnfs://{}/{}", self.origin, self.path.join("/"))
+    }
+}
+
+impl TryFrom<Url> for WnfsResource {
+    type Error = anyhow::Error;
+
+    fn try_from(value: Url) -> Result<Self> {
+        match (value.scheme(), value.host_str()) {
+            ("wnfs", Some(origin)) => Ok(WnfsResource {
+                origin: String::from(origin),
+                path: value
+                    .path_segments()
+                    .map(|segments| {
+                        segments
+                            .filter(|segment| !segment.is_empty())
+                            .map(String::from)
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+            }),
+            _ => Err(anyhow!("Could not interpret URI as a WNFS path: {}", value)),
+        }
+    }
+}
+
+/// WNFS abilities, ordered so that each implies those before it
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Debug)]
+pub enum WnfsAction {
+    Append,
+    Overwrite,
+}
+
+impl Ability for WnfsAction {}
+
+impl ToString for WnfsAction {
+    fn to_string(&self) -> String {
+        match self {
+            WnfsAction::Append => "wnfs/append",
+            WnfsAction::Overwrite => "wnfs/overwrite",
+        }
+        .into()
+    }
+}
+
+impl TryFrom<String> for WnfsAction {
+    type Error = anyhow::Error;
+
+    fn try_from(value: String) -> Result<Self> {
+        match value.as_str() {
+            "wnfs/append" => Ok(WnfsAction::Append),
+            "wnfs/overwrite" => Ok(WnfsAction::Overwrite),
+            _ => Err(anyhow!("Unrecognized action: {}", value)),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct WnfsSemantics {}
+
+impl CapabilitySemantics<WnfsResource, WnfsAction> for WnfsSemantics {}
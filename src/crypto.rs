@@ -1,30 +1,157 @@
-use anyhow::Result;
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
 use base64::{engine::general_purpose, Engine as _};
 use ed25519_zebra::{SigningKey as Ed25519PrivateKey, VerificationKey as Ed25519PublicKey};
-use ucan::crypto::did::KeyConstructorSlice;
+use k256::ecdsa::{
+    signature::{Signer, Verifier},
+    Signature as Secp256k1Signature, SigningKey as Secp256k1PrivateKey,
+    VerifyingKey as Secp256k1PublicKey,
+};
+use p256::ecdsa::{SigningKey as P256PrivateKey, VerifyingKey as P256PublicKey};
+use rand::{CryptoRng, RngCore};
+use rsa::{pkcs1::DecodeRsaPrivateKey, RsaPrivateKey, RsaPublicKey};
+use std::fmt;
+use ucan::crypto::{
+    did::{KeyConstructorSlice, DID_KEY_PREFIX},
+    KeyMaterial,
+};
 use ucan_key_support::{
     ed25519::{bytes_to_ed25519_key, Ed25519KeyMaterial, ED25519_MAGIC_BYTES},
-    rsa::{bytes_to_rsa_key, RSA_MAGIC_BYTES},
+    p256::{bytes_to_p256_key, P256KeyMaterial, P256_MAGIC_BYTES},
+    rsa::{bytes_to_rsa_key, RsaKeyMaterial, RSA_MAGIC_BYTES},
 };
 
 pub const SUPPORTED_KEYS: &KeyConstructorSlice = &[
     (ED25519_MAGIC_BYTES, bytes_to_ed25519_key),
     (RSA_MAGIC_BYTES, bytes_to_rsa_key),
+    (SECP256K1_MAGIC_BYTES, bytes_to_secp256k1_key),
+    (P256_MAGIC_BYTES, bytes_to_p256_key),
 ];
 
+/// The multicodec prefix for a compressed secp256k1 public key
+pub const SECP256K1_MAGIC_BYTES: &[u8] = &[0xe7, 0x01];
+
+/// secp256k1 key material signing with ES256K. `ucan-key-support` has no
+/// secp256k1 support, so it is implemented here.
+#[derive(Clone)]
+pub struct Secp256k1KeyMaterial(pub Secp256k1PublicKey, pub Option<Secp256k1PrivateKey>);
+
+// Written out by hand so the private key is never printed
+impl fmt::Debug for Secp256k1KeyMaterial {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Secp256k1KeyMaterial")
+            .field("public_key", &self.0)
+            .field("has_private_key", &self.1.is_some())
+            .finish()
+    }
+}
+
+#[async_trait]
+impl KeyMaterial for Secp256k1KeyMaterial {
+    fn get_jwt_algorithm_name(&self) -> String {
+        "ES256K".into()
+    }
+
+    async fn get_did(&self) -> Result<String> {
+        let public_key = self.0.to_encoded_point(true);
+        let bytes = [SECP256K1_MAGIC_BYTES, public_key.as_bytes()].concat();
+
+        Ok(format!(
+            "{}{}",
+            DID_KEY_PREFIX,
+            bs58::encode(bytes).into_string()
+        ))
+    }
+
+    async fn sign(&self, payload: &[u8]) -> Result<Vec<u8>> {
+        match &self.1 {
+            Some(private_key) => {
+                let signature: Secp256k1Signature = private_key.sign(payload);
+                Ok(signature.to_bytes().to_vec())
+            }
+            None => Err(anyhow!("No private key; cannot sign data")),
+        }
+    }
+
+    async fn verify(&self, payload: &[u8], signature: &[u8]) -> Result<()> {
+        let signature = Secp256k1Signature::from_slice(signature)?;
+
+        self.0
+            .verify(payload, &signature)
+            .map_err(|error| anyhow!("Could not verify signature: {:?}", error))
+    }
+}
+
+pub fn bytes_to_secp256k1_key(bytes: Vec<u8>) -> Result<Box<dyn KeyMaterial>> {
+    let public_key = Secp256k1PublicKey::from_sec1_bytes(&bytes)?;
+    Ok(Box::new(Secp256k1KeyMaterial(public_key, None)))
+}
+
 pub fn generate_ed25519_key() -> Ed25519KeyMaterial {
-    let private_key = Ed25519PrivateKey::new(rand::thread_rng());
+    generate_ed25519_key_from_rng(rand::thread_rng())
+}
+
+/// Generate a key from `rng`, so a seeded RNG gives the same key each run
+pub fn generate_ed25519_key_from_rng<R: RngCore + CryptoRng>(rng: R) -> Ed25519KeyMaterial {
+    let private_key = Ed25519PrivateKey::new(rng);
     let public_key = Ed25519PublicKey::from(&private_key);
     Ed25519KeyMaterial(public_key, Some(private_key))
 }
 
+/// Decode a base64 encoded Ed25519 private key, the first 32 bytes of which
+/// are the private half
 pub fn ed25519_key_from_base64(encoded_key: &str) -> Result<Ed25519KeyMaterial> {
-    let bytes = general_purpose::STANDARD.decode(encoded_key).unwrap();
-    let private_key_bytes: &[u8; 32] = bytes.as_slice()[0..32]
-        .try_into()
-        .expect("Could not extract private key");
-    let private_key = Ed25519PrivateKey::from(private_key_bytes.to_owned());
+    let bytes = general_purpose::STANDARD.decode(encoded_key)?;
+    let private_key_bytes: [u8; 32] = bytes
+        .get(0..32)
+        .and_then(|private_key_bytes| private_key_bytes.try_into().ok())
+        .ok_or_else(|| {
+            anyhow!(
+                "An Ed25519 private key needs 32 bytes, but only {} were given",
+                bytes.len()
+            )
+        })?;
+    let private_key = Ed25519PrivateKey::from(private_key_bytes);
     let public_key = Ed25519PublicKey::from(&private_key);
 
     Ok(Ed25519KeyMaterial(public_key, Some(private_key)))
 }
+
+/// Decode a base64 PKCS#1 DER encoded RSA private key
+pub fn rsa_key_from_base64(encoded_key: &str) -> Result<RsaKeyMaterial> {
+    let bytes = general_purpose::STANDARD.decode(encoded_key)?;
+    let private_key = RsaPrivateKey::from_pkcs1_der(&bytes)?;
+    let public_key = RsaPublicKey::from(&private_key);
+
+    Ok(RsaKeyMaterial(public_key, Some(private_key)))
+}
+
+pub fn generate_secp256k1_key() -> Secp256k1KeyMaterial {
+    let private_key = Secp256k1PrivateKey::random(&mut rand::thread_rng());
+    let public_key = Secp256k1PublicKey::from(&private_key);
+    Secp256k1KeyMaterial(public_key, Some(private_key))
+}
+
+/// Decode a base64 encoded 32 byte secp256k1 private key
+pub fn secp256k1_key_from_base64(encoded_key: &str) -> Result<Secp256k1KeyMaterial> {
+    let bytes = general_purpose::STANDARD.decode(encoded_key)?;
+    let private_key = Secp256k1PrivateKey::from_slice(&bytes)?;
+    let public_key = Secp256k1PublicKey::from(&private_key);
+
+    Ok(Secp256k1KeyMaterial(public_key, Some(private_key)))
+}
+
+pub fn generate_p256_key() -> P256KeyMaterial {
+    let private_key = P256PrivateKey::random(&mut rand::thread_rng());
+    let public_key = P256PublicKey::from(&private_key);
+    P256KeyMaterial(public_key, Some(private_key))
+}
+
+/// Decode a base64 encoded 32 byte P-256 private key
+pub fn p256_key_from_base64(encoded_key: &str) -> Result<P256KeyMaterial> {
+    let bytes = general_purpose::STANDARD.decode(encoded_key)?;
+    let private_key = P256PrivateKey::from_slice(&bytes)?;
+    let public_key = P256PublicKey::from(&private_key);
+
+    Ok(P256KeyMaterial(public_key, Some(private_key)))
+}
@@ -1,59 +1,333 @@
+use crate::{
+    generators::assertions::UcanAssertions,
+    identities::{Identities, KeyTypeIdentities, ALICE_DID},
+};
+use anyhow::{ensure, Context, Result};
+use base64::{engine::general_purpose, Engine as _};
 use cid::multihash::Code;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::{collections::BTreeMap, default::Default};
-use ucan::{builder::Signable, capability::Capability, Ucan};
+use std::{collections::BTreeMap, rc::Rc, str::FromStr};
+use ucan::{
+    builder::Signable,
+    capability::Capability,
+    crypto::KeyMaterial,
+    ucan::{UcanHeader, UcanPayload},
+    Ucan,
+};
 use ucan_key_support::ed25519::Ed25519KeyMaterial;
 
+pub use crate::capabilities;
+
 pub mod assertions;
 pub mod build;
+pub mod disprove;
+pub mod errors;
+pub mod imported;
 pub mod mutate;
+pub mod prove;
+pub mod redact;
 pub mod refute;
+pub mod schema;
 pub mod to_cid;
+pub mod validate;
 pub mod verify;
 
-#[derive(Debug)]
+/// A fixture of any task, serialized as the fixture itself
+#[derive(Clone, Debug, Serialize)]
+#[serde(untagged)]
+pub enum AnyFixture {
+    Verify(verify::VerifyFixture),
+    Refute(refute::RefuteFixture),
+    Prove(prove::ProveFixture),
+    Disprove(disprove::DisproveFixture),
+    Build(build::BuildFixture),
+    ToCID(to_cid::ToCIDFixture),
+}
+
+impl AnyFixture {
+    pub fn name(&self) -> &str {
+        match self {
+            AnyFixture::Verify(fixture) => fixture.name(),
+            AnyFixture::Refute(fixture) => fixture.name(),
+            AnyFixture::Prove(fixture) => fixture.name(),
+            AnyFixture::Disprove(fixture) => fixture.name(),
+            AnyFixture::Build(fixture) => fixture.name(),
+            AnyFixture::ToCID(fixture) => fixture.name(),
+        }
+    }
+
+    /// The task named in the fixture's `task` field
+    pub fn task(&self) -> &'static str {
+        match self {
+            AnyFixture::Verify(_) => "verify",
+            AnyFixture::Refute(_) => "refute",
+            AnyFixture::Prove(_) => "prove",
+            AnyFixture::Disprove(_) => "disprove",
+            AnyFixture::Build(_) => "build",
+            AnyFixture::ToCID(_) => "toCID",
+        }
+    }
+}
+
+/// The category every task's fixtures are grouped by
+pub(crate) trait Fixture {
+    fn category_mut(&mut self) -> &mut String;
+}
+
+/// A fixture with an input token and assertions about it
+pub(crate) trait TokenFixture {
+    fn token_and_assertions(&mut self) -> (&str, &mut UcanAssertions);
+}
+
+/// Tag each fixture in a group with the category reported by `--stats`
+pub(crate) fn categorize<F: Fixture>(category: &str, mut fixtures: Vec<F>) -> Vec<F> {
+    for fixture in fixtures.iter_mut() {
+        *fixture.category_mut() = category.to_string();
+    }
+
+    fixtures
+}
+
+/// Record the decoded header and payload of each fixture's final token,
+/// once every mutation has been applied
+pub(crate) fn with_decoded<F: TokenFixture>(mut fixtures: Vec<F>) -> Vec<F> {
+    for fixture in fixtures.iter_mut() {
+        let (token, assertions) = fixture.token_and_assertions();
+        assertions.decode_token(token);
+    }
+
+    fixtures
+}
+
+/// Every generated fixture, by task in the order they are written to
+/// `all.json`. Build fixtures embed their issuer's private key, so they
+/// are only issued by the default identities and are left out otherwise.
+pub async fn all_fixtures(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    key_types: &KeyTypeIdentities,
+    version: &str,
+) -> Result<Vec<AnyFixture>> {
+    let default_issuer = identities.alice_did == ALICE_DID;
+
+    // Generators share the identities through an `Rc`, so they run
+    // concurrently on this task rather than in parallel
+    let (verify, refute, prove, disprove, build, to_cid) = futures::try_join!(
+        verify::generate(identities.clone(), key_types, version),
+        refute::generate(identities.clone(), version),
+        prove::generate(identities.clone(), version),
+        disprove::generate(identities.clone(), version),
+        async {
+            if default_issuer {
+                build::generate(identities.clone(), key_types, version).await
+            } else {
+                Ok(vec![])
+            }
+        },
+        to_cid::generate(identities.clone(), version),
+    )?;
+
+    let fixtures: Vec<AnyFixture> = verify
+        .into_iter()
+        .map(AnyFixture::Verify)
+        .chain(refute.into_iter().map(AnyFixture::Refute))
+        .chain(prove.into_iter().map(AnyFixture::Prove))
+        .chain(disprove.into_iter().map(AnyFixture::Disprove))
+        .chain(build.into_iter().map(AnyFixture::Build))
+        .chain(to_cid.into_iter().map(AnyFixture::ToCID))
+        .collect();
+
+    Ok(fixtures)
+}
+
+/// How strictly the spec requires a fixture to pass, so a harness can
+/// report MUST failures separately from SHOULD and MAY ones
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Conformance {
+    #[default]
+    Must,
+    Should,
+    May,
+}
+
+/// UCAN versions fixtures are generated for, each into its own
+/// `fixtures/{version}` directory. Every generator is passed the version
+/// and signs its payloads with it as the `ucv`.
+pub const UCAN_VERSIONS: &[&str] = &["0.10.0"];
+
+/// The clock skew tolerated by fixtures at a time bound
+pub const CLOCK_SKEW_SECONDS: u64 = 60;
+
+/// The unix timestamp fixtures at a time bound are evaluated at
+pub const CLOCK_SKEW_TIME: u64 = 1_700_000_000;
+
+#[derive(Clone, Debug)]
 pub struct UcanOptions {
+    /// The `ucv` the payload is signed with
+    version: String,
     capabilities: Vec<Capability>,
     expiration: Option<u64>,
     not_before: Option<u64>,
     facts: BTreeMap<String, Value>,
     proofs: Vec<String>,
-    add_nonce: bool,
+    /// A fixed nonce, so the token is the same on every run
+    nonce: Option<String>,
 }
 
-#[allow(clippy::derivable_impls)]
-impl Default for UcanOptions {
-    fn default() -> Self {
+impl UcanOptions {
+    /// Options for a payload of UCAN `version` with nothing else set
+    pub fn for_version(version: &str) -> Self {
         UcanOptions {
+            version: String::from(version),
             capabilities: vec![],
             expiration: None,
             not_before: None,
             facts: BTreeMap::new(),
             proofs: vec![],
-            add_nonce: false,
+            nonce: None,
         }
     }
 }
 
-pub async fn make_proof(
-    issuer: &Ed25519KeyMaterial,
+pub async fn make_proof<K: KeyMaterial>(
+    issuer: &K,
     audience: String,
     options: UcanOptions,
-) -> (String, String) {
+) -> Result<(String, String)> {
+    let ucan = sign_with_options(issuer, audience, options).await?;
+
+    let cid = ucan.to_cid(Code::Sha2_256)?.to_string();
+    let token = Ucan::encode(&ucan)?;
+
+    Ok((cid, token))
+}
+
+/// A delegation chain of one UCAN per entry in `hops`, each delegating its
+/// capabilities from alice to bob to mallory, and on around again. Each
+/// UCAN cites the one before it. Returns the leaf token and the proof
+/// tokens it rests on, root first.
+pub async fn make_proof_chain<K: KeyMaterial + Clone + 'static>(
+    identities: &Identities<K>,
+    hops: Vec<Vec<Capability>>,
+    version: &str,
+) -> Result<(String, Vec<String>)> {
+    ensure!(!hops.is_empty(), "A proof chain needs at least one UCAN");
+
+    let keys = [
+        &identities.alice_key,
+        &identities.bob_key,
+        &identities.mallory_key,
+    ];
+    let dids = [
+        &identities.alice_did,
+        &identities.bob_did,
+        &identities.mallory_did,
+    ];
+
+    let mut tokens: Vec<String> = vec![];
+    let mut proofs: Vec<String> = vec![];
+    for (hop, capabilities) in hops.into_iter().enumerate() {
+        let (cid, token) = make_proof(
+            keys[hop % keys.len()],
+            dids[(hop + 1) % dids.len()].clone(),
+            UcanOptions {
+                capabilities,
+                proofs,
+                ..UcanOptions::for_version(version)
+            },
+        )
+        .await?;
+
+        proofs = vec![cid];
+        tokens.push(token);
+    }
+
+    let leaf = tokens.pop().unwrap();
+
+    Ok((leaf, tokens))
+}
+
+pub fn token_to_cid(token: &str) -> Result<String> {
+    let ucan = Ucan::from_str(token)?;
+
+    Ok(ucan.to_cid(Code::Sha2_256)?.to_string())
+}
+
+/// Encode a header or payload as the base64url segment of a token
+fn encode_part<T: Serialize>(part: &T) -> String {
+    general_purpose::URL_SAFE_NO_PAD.encode(part_json(part))
+}
+
+/// A header or payload as the JSON text `encode_part` encodes
+fn part_json<T: Serialize>(part: &T) -> String {
+    serde_json::to_string(part).unwrap()
+}
+
+/// The payload JSON of a token signed by `issuer` with these options, as
+/// `encode_part` would encode it. Hand-built tokens start from this
+/// canonical baseline and are signed with `mutate::build_raw_token`.
+pub async fn encode_payload<K: KeyMaterial>(
+    issuer: &K,
+    audience: String,
+    options: &UcanOptions,
+) -> Result<String> {
+    let (_, payload) = header_and_payload(issuer, audience, options.clone()).await?;
+
+    Ok(part_json(&payload))
+}
+
+async fn header_and_payload<K: KeyMaterial>(
+    issuer: &K,
+    audience: String,
+    options: UcanOptions,
+) -> Result<(UcanHeader, UcanPayload)> {
     let signable = Signable {
-        issuer: &issuer.clone(),
-        audience: audience.clone(),
+        issuer,
+        audience,
         capabilities: options.capabilities,
         expiration: options.expiration,
         not_before: options.not_before,
         facts: options.facts,
         proofs: options.proofs,
-        add_nonce: options.add_nonce,
+        add_nonce: false,
     };
-    let ucan = signable.sign().await.unwrap();
 
-    let cid = ucan.to_cid(Code::Sha2_256).unwrap().to_string();
-    let token = Ucan::encode(&ucan).unwrap();
+    let mut payload = signable
+        .ucan_payload()
+        .await
+        .context("Could not build UCAN payload")?;
+    payload.ucv = options.version;
+    payload.nnc = options.nonce;
+
+    Ok((signable.ucan_header(), payload))
+}
+
+/// Sign a UCAN for the options, with the fixed nonce if one is set
+pub async fn sign_with_options<K: KeyMaterial>(
+    issuer: &K,
+    audience: String,
+    options: UcanOptions,
+) -> Result<Ucan> {
+    let (header, payload) = header_and_payload(issuer, audience, options).await?;
+
+    sign_ucan(issuer, header, payload).await
+}
+
+/// Sign a header and payload as given, for payloads `Signable` cannot build
+pub async fn sign_ucan<K: KeyMaterial>(
+    issuer: &K,
+    header: UcanHeader,
+    payload: UcanPayload,
+) -> Result<Ucan> {
+    let data_to_sign = format!("{}.{}", encode_part(&header), encode_part(&payload))
+        .as_bytes()
+        .to_vec();
+    let signature = issuer
+        .sign(data_to_sign.as_slice())
+        .await
+        .context("Could not sign UCAN")?;
 
-    (cid, token)
+    Ok(Ucan::new(header, payload, data_to_sign, signature))
 }
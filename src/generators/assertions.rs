@@ -1,4 +1,8 @@
+use super::schema::{nullable_u64, CapabilitiesSchema, FactsSchema};
+use base64::{engine::general_purpose, Engine as _};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use serde_with::{
     base64::{Base64, UrlSafe},
     formats::Unpadded,
@@ -7,15 +11,47 @@ use serde_with::{
 use ucan::{capability::Capabilities, ucan::FactsMap, Ucan};
 
 #[serde_as]
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
 pub struct UcanAssertions {
     pub header: UcanHeaderAssertions,
     pub payload: UcanPayloadAssertions,
     #[serde_as(as = "Base64<UrlSafe, Unpadded>")]
+    #[schemars(with = "String")]
     signature: Vec<u8>,
+    /// The header and payload as decoded from the token, free of the
+    /// quirks of the fields above
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub decoded: Option<DecodedToken>,
+}
+
+impl UcanAssertions {
+    /// Record the decoded header and payload of `token`, or nothing if
+    /// either segment is not base64url encoded JSON
+    pub fn decode_token(&mut self, token: &str) {
+        let segments: Vec<&str> = token.split('.').collect();
+
+        self.decoded = match segments[..] {
+            [header, payload, ..] => decode_segment(header)
+                .zip(decode_segment(payload))
+                .map(|(header, payload)| DecodedToken { header, payload }),
+            _ => None,
+        };
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct DecodedToken {
+    pub header: Value,
+    pub payload: Value,
+}
+
+fn decode_segment(segment: &str) -> Option<Value> {
+    let bytes = general_purpose::URL_SAFE_NO_PAD.decode(segment).ok()?;
+
+    serde_json::from_slice(&bytes).ok()
 }
 
-#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq, JsonSchema)]
 pub struct UcanHeaderAssertions {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub alg: Option<String>,
@@ -33,7 +69,7 @@ impl UcanHeaderAssertions {
     }
 }
 
-#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq, JsonSchema)]
 pub struct UcanPayloadAssertions {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub ucv: Option<String>,
@@ -42,14 +78,17 @@ pub struct UcanPayloadAssertions {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub aud: Option<String>,
     #[serde(skip_serializing_if = "is_skip_expiration_marker")]
+    #[schemars(schema_with = "nullable_u64")]
     pub exp: Option<u64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub nbf: Option<u64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub nnc: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[schemars(with = "Option<CapabilitiesSchema>")]
     pub cap: Option<Capabilities>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[schemars(with = "Option<FactsSchema>")]
     pub fct: Option<FactsMap>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub prf: Option<Vec<String>>,
@@ -119,5 +158,30 @@ pub fn ucan_to_assertions(ucan: Ucan) -> UcanAssertions {
             prf: ucan.proofs().clone(),
         },
         signature: ucan.signature().to_vec(),
+        decoded: None,
+    }
+}
+
+/// Assertions for a token that cannot be decoded, with every field omitted
+pub fn empty_assertions() -> UcanAssertions {
+    UcanAssertions {
+        header: UcanHeaderAssertions {
+            alg: None,
+            typ: None,
+        },
+        payload: UcanPayloadAssertions {
+            ucv: None,
+            iss: None,
+            aud: None,
+            // Some(86) is a special marker value to remove exp from the assertions
+            exp: Some(86),
+            nbf: None,
+            nnc: None,
+            cap: None,
+            fct: None,
+            prf: None,
+        },
+        signature: vec![],
+        decoded: None,
     }
 }
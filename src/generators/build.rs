@@ -1,27 +1,38 @@
-use super::UcanOptions;
+use super::{
+    categorize, make_proof,
+    mutate::sort_keys,
+    schema::{nullable_u64, CapabilitiesSchema, FactsSchema},
+    sign_with_options, Conformance, Fixture, UcanOptions,
+};
 use crate::{
     capabilities::EmailSemantics,
-    identities::{Identities, ALICE_BASE64_KEY},
+    identities::{
+        Identities, KeyTypeIdentities, ALICE_BASE64_KEY, ALICE_RSA_BASE64_KEY, BOB_BASE64_KEY,
+    },
 };
-use anyhow::Result;
+use anyhow::{Context, Result};
+use futures::{future::try_join_all, FutureExt};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use std::collections::BTreeMap;
-use std::{default::Default, rc::Rc};
+use std::rc::Rc;
 use ucan::{
-    builder::Signable,
     capability::{Capabilities, Capability, CapabilitySemantics},
+    crypto::KeyMaterial,
     ucan::FactsMap,
     Ucan,
 };
-use ucan_key_support::ed25519::Ed25519KeyMaterial;
+use ucan_key_support::{ed25519::Ed25519KeyMaterial, rsa::RsaKeyMaterial};
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
 pub struct BuildFixture {
-    name: String,
-    task: String,
-    inputs: Inputs,
-    outputs: Outputs,
+    pub name: String,
+    pub task: String,
+    pub category: String,
+    pub conformance: Conformance,
+    pub inputs: Inputs,
+    pub outputs: Outputs,
 }
 
 impl BuildFixture {
@@ -29,72 +40,124 @@ impl BuildFixture {
         BuildFixture {
             name,
             task: "build".to_string(),
+            category: String::new(),
+            conformance: Conformance::Must,
             inputs,
             outputs,
         }
     }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-struct Inputs {
-    version: String,
+impl Fixture for BuildFixture {
+    fn category_mut(&mut self) -> &mut String {
+        &mut self.category
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct Inputs {
+    pub version: String,
     issuer_base64_key: String,
-    signature_scheme: String,
-    audience: String,
+    pub signature_scheme: String,
+    pub audience: String,
     #[serde(skip_serializing_if = "Option::is_none")]
-    not_before: Option<u64>,
-    expiration: Option<u64>,
+    pub not_before: Option<u64>,
+    #[schemars(schema_with = "nullable_u64")]
+    pub expiration: Option<u64>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    facts: Option<FactsMap>,
-    capabilities: Capabilities,
+    #[schemars(with = "Option<FactsSchema>")]
+    pub facts: Option<FactsMap>,
+    #[schemars(with = "CapabilitiesSchema")]
+    pub capabilities: Capabilities,
+    /// Proof tokens, whose CIDs the built token lists in `prf`
+    pub proofs: Vec<String>,
+    /// How the header and payload are serialized before signing.
+    /// `sorted-json` is compact JSON with the keys of every object sorted by
+    /// their UTF-8 bytes.
+    pub canonicalization: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
 pub struct Outputs {
-    token: String,
+    pub token: String,
 }
 
 const EMAIL_SEMANTICS: EmailSemantics = EmailSemantics {};
 
+/// Output tokens are re-signed with sorted keys, so the signature does not
+/// depend on the key order of our serializer
+const CANONICALIZATION: &str = "sorted-json";
+
 // GENERATE
 
-pub async fn generate() -> Result<Vec<BuildFixture>> {
-    let identities = Rc::new(Identities::new().await);
+/// Fixtures embed `ALICE_BASE64_KEY` as the issuer key, so `identities`
+/// must be the defaults from `Identities::new`
+pub async fn generate(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    key_types: &KeyTypeIdentities,
+    version: &str,
+) -> Result<Vec<BuildFixture>> {
+    let rsa_identities = key_types.rsa.clone();
 
-    let fixtures: Vec<BuildFixture> = vec![
-        // Time bounds
-        has_expiration(identities.clone()).await,
-        has_not_before(identities.clone()).await,
-        // Capability
-        send_email_as_alice(identities.clone()).await,
-        send_newsletter_as_alice(identities.clone()).await,
-        // Facts
-        has_fact(identities.clone()).await,
-    ];
+    let fixtures: Vec<BuildFixture> = [
+        categorize(
+            "time",
+            try_join_all(vec![
+                has_expiration(identities.clone(), version).boxed_local(),
+                has_not_before(identities.clone(), version).boxed_local(),
+            ])
+            .await?,
+        ),
+        categorize(
+            "capability",
+            try_join_all(vec![
+                send_email_as_alice(identities.clone(), version).boxed_local(),
+                send_newsletter_as_alice(identities.clone(), version).boxed_local(),
+            ])
+            .await?,
+        ),
+        categorize(
+            "facts",
+            try_join_all(vec![has_fact(identities.clone(), version).boxed_local()]).await?,
+        ),
+        categorize(
+            "signature",
+            try_join_all(vec![
+                rsa_signed(rsa_identities.clone(), version).boxed_local()
+            ])
+            .await?,
+        ),
+        categorize(
+            "delegation",
+            try_join_all(vec![
+                has_delegated_proof(identities.clone(), version).boxed_local()
+            ])
+            .await?,
+        ),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
 
     Ok(fixtures)
 }
 
-async fn make_fixture(
+async fn make_fixture<K: KeyMaterial>(
     name: String,
-    issuer: &Ed25519KeyMaterial,
+    issuer: &K,
     issuer_base64_key: String,
     signature_scheme: String,
     audience: String,
     options: UcanOptions,
-) -> BuildFixture {
-    let signable = Signable {
-        issuer: &issuer.clone(),
-        audience: audience.clone(),
-        capabilities: options.capabilities,
-        expiration: options.expiration,
-        not_before: options.not_before,
-        facts: options.facts,
-        proofs: options.proofs,
-        add_nonce: options.add_nonce,
-    };
-
-    let ucan = signable.sign().await.unwrap();
+    proofs: Vec<String>,
+) -> Result<BuildFixture> {
+    let ucan = sign_with_options(issuer, audience.clone(), options)
+        .await
+        .with_context(|| format!("Could not sign fixture: {}", name))?;
 
     let inputs = Inputs {
         version: ucan.version().into(),
@@ -105,17 +168,22 @@ async fn make_fixture(
         expiration: *ucan.expires_at(),
         facts: ucan.facts().clone(),
         capabilities: ucan.capabilities().clone(),
+        proofs,
+        canonicalization: String::from(CANONICALIZATION),
     };
 
-    let token = Ucan::encode(&ucan).unwrap();
+    let token = sort_keys(&Ucan::encode(&ucan)?, issuer).await?;
     let outputs = Outputs { token };
 
-    BuildFixture::new(name, inputs, outputs)
+    Ok(BuildFixture::new(name, inputs, outputs))
 }
 
 // TIME BOUNDS
 
-async fn has_expiration(identities: Rc<Identities<Ed25519KeyMaterial>>) -> BuildFixture {
+async fn has_expiration(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<BuildFixture> {
     make_fixture(
         String::from("UCAN has an expiration"),
         &identities.alice_key,
@@ -124,13 +192,17 @@ async fn has_expiration(identities: Rc<Identities<Ed25519KeyMaterial>>) -> Build
         identities.bob_did.clone(),
         UcanOptions {
             expiration: Some(9246211200),
-            ..Default::default()
+            ..UcanOptions::for_version(version)
         },
+        vec![],
     )
     .await
 }
 
-async fn has_not_before(identities: Rc<Identities<Ed25519KeyMaterial>>) -> BuildFixture {
+async fn has_not_before(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<BuildFixture> {
     make_fixture(
         String::from("UCAN has a not before"),
         &identities.alice_key,
@@ -139,15 +211,19 @@ async fn has_not_before(identities: Rc<Identities<Ed25519KeyMaterial>>) -> Build
         identities.bob_did.clone(),
         UcanOptions {
             not_before: Some(1),
-            ..Default::default()
+            ..UcanOptions::for_version(version)
         },
+        vec![],
     )
     .await
 }
 
 // CAPABILITY
 
-async fn send_email_as_alice(identities: Rc<Identities<Ed25519KeyMaterial>>) -> BuildFixture {
+async fn send_email_as_alice(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<BuildFixture> {
     let send_email_as_alice: Capability = EMAIL_SEMANTICS
         .parse("mailto:alice@email.com", "email/send", None)
         .unwrap()
@@ -161,13 +237,17 @@ async fn send_email_as_alice(identities: Rc<Identities<Ed25519KeyMaterial>>) ->
         identities.bob_did.clone(),
         UcanOptions {
             capabilities: vec![send_email_as_alice],
-            ..Default::default()
+            ..UcanOptions::for_version(version)
         },
+        vec![],
     )
     .await
 }
 
-async fn send_newsletter_as_alice(identities: Rc<Identities<Ed25519KeyMaterial>>) -> BuildFixture {
+async fn send_newsletter_as_alice(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<BuildFixture> {
     let caveat = json!({"templates": ["newsletter"]});
     let send_email_as_alice: Capability = EMAIL_SEMANTICS
         .parse("mailto:alice@email.com", "email/send", Some(&caveat))
@@ -182,15 +262,19 @@ async fn send_newsletter_as_alice(identities: Rc<Identities<Ed25519KeyMaterial>>
         identities.bob_did.clone(),
         UcanOptions {
             capabilities: vec![send_email_as_alice],
-            ..Default::default()
+            ..UcanOptions::for_version(version)
         },
+        vec![],
     )
     .await
 }
 
 // FACTS
 
-async fn has_fact(identities: Rc<Identities<Ed25519KeyMaterial>>) -> BuildFixture {
+async fn has_fact(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<BuildFixture> {
     make_fixture(
         String::from("UCAN has a fact with a challenge"),
         &identities.alice_key,
@@ -199,8 +283,64 @@ async fn has_fact(identities: Rc<Identities<Ed25519KeyMaterial>>) -> BuildFixtur
         identities.bob_did.clone(),
         UcanOptions {
             facts: BTreeMap::from([(String::from("challenge"), json!("abcdef"))]),
-            ..Default::default()
+            ..UcanOptions::for_version(version)
+        },
+        vec![],
+    )
+    .await
+}
+
+// SIGNATURE
+
+async fn rsa_signed(
+    identities: Rc<Identities<RsaKeyMaterial>>,
+    version: &str,
+) -> Result<BuildFixture> {
+    make_fixture(
+        String::from("UCAN builds with an RSA signature"),
+        &identities.alice_key,
+        String::from(ALICE_RSA_BASE64_KEY),
+        String::from("RS256"),
+        identities.bob_did.clone(),
+        UcanOptions::for_version(version),
+        vec![],
+    )
+    .await
+}
+
+// DELEGATION
+
+async fn has_delegated_proof(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<BuildFixture> {
+    let send_email_as_alice: Capability = EMAIL_SEMANTICS
+        .parse("mailto:alice@email.com", "email/send", None)
+        .unwrap()
+        .into();
+
+    let (proof_ucan_cid, proof_token) = make_proof(
+        &identities.alice_key,
+        identities.bob_did.clone(),
+        UcanOptions {
+            capabilities: vec![send_email_as_alice.clone()],
+            ..UcanOptions::for_version(version)
+        },
+    )
+    .await?;
+
+    make_fixture(
+        String::from("UCAN builds with a delegated proof"),
+        &identities.bob_key,
+        String::from(BOB_BASE64_KEY),
+        String::from("Ed25519"),
+        identities.mallory_did.clone(),
+        UcanOptions {
+            capabilities: vec![send_email_as_alice],
+            proofs: vec![proof_ucan_cid],
+            ..UcanOptions::for_version(version)
         },
+        vec![proof_token],
     )
     .await
 }
@@ -0,0 +1,418 @@
+use super::{
+    assertions::{ucan_to_assertions, UcanAssertions},
+    categorize, make_proof, sign_with_options, with_decoded, Conformance, Fixture, TokenFixture,
+    UcanOptions,
+};
+use crate::{
+    capabilities::{EmailSemantics, HttpSemantics},
+    identities::Identities,
+};
+use anyhow::{Context, Result};
+use futures::{future::try_join_all, FutureExt};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::rc::Rc;
+use ucan::{
+    capability::{Capability, CapabilitySemantics},
+    crypto::KeyMaterial,
+    Ucan,
+};
+use ucan_key_support::ed25519::Ed25519KeyMaterial;
+
+/// A fixture asserting that a structurally valid token does not grant
+/// `capabilities`, originating from `issuer`, at the unix timestamp `time`
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct DisproveFixture {
+    pub name: String,
+    pub task: String,
+    pub category: String,
+    pub conformance: Conformance,
+    pub inputs: Inputs,
+    pub assertions: UcanAssertions,
+    #[schemars(with = "Vec<Value>")]
+    pub capabilities: Vec<Capability>,
+    pub issuer: String,
+    pub time: u64,
+}
+
+impl DisproveFixture {
+    fn new(
+        name: String,
+        inputs: Inputs,
+        assertions: UcanAssertions,
+        capabilities: Vec<Capability>,
+        issuer: String,
+    ) -> Self {
+        DisproveFixture {
+            name,
+            task: "disprove".to_string(),
+            category: String::new(),
+            conformance: Conformance::Must,
+            inputs,
+            assertions,
+            capabilities,
+            issuer,
+            time: DISPROVE_TIME,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl Fixture for DisproveFixture {
+    fn category_mut(&mut self) -> &mut String {
+        &mut self.category
+    }
+}
+
+impl TokenFixture for DisproveFixture {
+    fn token_and_assertions(&mut self) -> (&str, &mut UcanAssertions) {
+        (self.inputs.token.as_str(), &mut self.assertions)
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct Inputs {
+    pub token: String,
+    pub proofs: Vec<String>,
+}
+
+const EMAIL_SEMANTICS: EmailSemantics = EmailSemantics {};
+const HTTP_SEMANTICS: HttpSemantics = HttpSemantics {};
+
+/// Fixtures query capabilities at this time. Their tokens have no time
+/// bounds, so only the capabilities decide the outcome.
+const DISPROVE_TIME: u64 = 1_700_000_000;
+
+// GENERATE
+
+pub async fn generate(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<Vec<DisproveFixture>> {
+    let fixtures: Vec<DisproveFixture> = [
+        categorize(
+            "delegation",
+            try_join_all(vec![
+                claimed_capability_not_delegated(identities.clone(), version).boxed_local(),
+                http_method_not_delegated(identities.clone(), version).boxed_local(),
+            ])
+            .await?,
+        ),
+        categorize(
+            "caveats",
+            try_join_all(vec![
+                caveats_escalate_with_new_caveat(identities.clone(), version).boxed_local(),
+                caveats_escalate_to_no_caveats(identities.clone(), version).boxed_local(),
+                caveats_escalate_with_different_caveat(identities.clone(), version).boxed_local(),
+                caveats_escalate_after_two_hops(identities.clone(), version).boxed_local(),
+            ])
+            .await?,
+        ),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+
+    Ok(with_decoded(fixtures))
+}
+
+/// Sign a token claiming `options.capabilities` and query those same
+/// capabilities from `root_issuer`
+async fn make_fixture<K: KeyMaterial>(
+    name: String,
+    issuer: &K,
+    audience: String,
+    options: UcanOptions,
+    proofs: Vec<String>,
+    root_issuer: String,
+) -> Result<DisproveFixture> {
+    let capabilities = options.capabilities.clone();
+    let ucan = sign_with_options(issuer, audience.clone(), options)
+        .await
+        .with_context(|| format!("Could not sign fixture: {}", name))?;
+
+    let inputs = Inputs {
+        token: Ucan::encode(&ucan)?,
+        proofs,
+    };
+    let assertions = ucan_to_assertions(ucan);
+
+    Ok(DisproveFixture::new(
+        name,
+        inputs,
+        assertions,
+        capabilities,
+        root_issuer,
+    ))
+}
+
+// DELEGATION
+
+async fn claimed_capability_not_delegated(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<DisproveFixture> {
+    let send_email_as_alice: Capability = EMAIL_SEMANTICS
+        .parse("mailto:alice@email.com", "email/send", None)
+        .unwrap()
+        .into();
+
+    let (proof_ucan_cid, proof_token) = make_proof(
+        &identities.alice_key,
+        identities.bob_did.clone(),
+        UcanOptions {
+            ..UcanOptions::for_version(version)
+        },
+    )
+    .await?;
+
+    make_fixture(
+        String::from("UCAN claims a capability that has not been delegated"),
+        &identities.bob_key,
+        identities.mallory_did.clone(),
+        UcanOptions {
+            capabilities: vec![send_email_as_alice],
+            proofs: vec![proof_ucan_cid],
+            ..UcanOptions::for_version(version)
+        },
+        vec![proof_token],
+        identities.alice_did.clone(),
+    )
+    .await
+}
+
+// Methods do not subsume each other, so a proof of http/GET does not
+// grant http/POST
+async fn http_method_not_delegated(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<DisproveFixture> {
+    let get_api: Capability = HTTP_SEMANTICS
+        .parse("https://example.com/api", "http/GET", None)
+        .unwrap()
+        .into();
+    let post_to_api: Capability = HTTP_SEMANTICS
+        .parse("https://example.com/api", "http/POST", None)
+        .unwrap()
+        .into();
+
+    let (proof_ucan_cid, proof_token) = make_proof(
+        &identities.alice_key,
+        identities.bob_did.clone(),
+        UcanOptions {
+            capabilities: vec![get_api],
+            ..UcanOptions::for_version(version)
+        },
+    )
+    .await?;
+
+    make_fixture(
+        String::from("UCAN claims a different method than its http proof"),
+        &identities.bob_key,
+        identities.mallory_did.clone(),
+        UcanOptions {
+            capabilities: vec![post_to_api],
+            proofs: vec![proof_ucan_cid],
+            ..UcanOptions::for_version(version)
+        },
+        vec![proof_token],
+        identities.alice_did.clone(),
+    )
+    .await
+}
+
+// CAVEATS
+
+async fn caveats_escalate_with_new_caveat(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<DisproveFixture> {
+    let caveat = json!({"templates": ["newsletter"]});
+    let send_email_as_alice: Capability = EMAIL_SEMANTICS
+        .parse("mailto:alice@email.com", "email/send", Some(&caveat))
+        .unwrap()
+        .into();
+
+    let (proof_ucan_cid, proof_token) = make_proof(
+        &identities.alice_key,
+        identities.bob_did.clone(),
+        UcanOptions {
+            capabilities: vec![send_email_as_alice],
+            ..UcanOptions::for_version(version)
+        },
+    )
+    .await?;
+
+    let escalated_caveat = json!({"templates": ["newsletter", "marketing"]});
+    let send_email_as_alice_escalated: Capability = EMAIL_SEMANTICS
+        .parse(
+            "mailto:alice@email.com",
+            "email/send",
+            Some(&escalated_caveat),
+        )
+        .unwrap()
+        .into();
+
+    make_fixture(
+        String::from("UCAN escalates by adding a new caveat"),
+        &identities.bob_key,
+        identities.mallory_did.clone(),
+        UcanOptions {
+            capabilities: vec![send_email_as_alice_escalated],
+            proofs: vec![proof_ucan_cid],
+            ..UcanOptions::for_version(version)
+        },
+        vec![proof_token],
+        identities.alice_did.clone(),
+    )
+    .await
+}
+
+async fn caveats_escalate_to_no_caveats(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<DisproveFixture> {
+    let caveat = json!({"templates": ["newsletter"]});
+    let send_email_as_alice: Capability = EMAIL_SEMANTICS
+        .parse("mailto:alice@email.com", "email/send", Some(&caveat))
+        .unwrap()
+        .into();
+
+    let (proof_ucan_cid, proof_token) = make_proof(
+        &identities.alice_key,
+        identities.bob_did.clone(),
+        UcanOptions {
+            capabilities: vec![send_email_as_alice],
+            ..UcanOptions::for_version(version)
+        },
+    )
+    .await?;
+
+    let send_email_as_alice_escalated: Capability = EMAIL_SEMANTICS
+        .parse("mailto:alice@email.com", "email/send", None)
+        .unwrap()
+        .into();
+
+    make_fixture(
+        String::from("UCAN escalates to no caveats"),
+        &identities.bob_key,
+        identities.mallory_did.clone(),
+        UcanOptions {
+            capabilities: vec![send_email_as_alice_escalated],
+            proofs: vec![proof_ucan_cid],
+            ..UcanOptions::for_version(version)
+        },
+        vec![proof_token],
+        identities.alice_did.clone(),
+    )
+    .await
+}
+
+async fn caveats_escalate_with_different_caveat(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<DisproveFixture> {
+    let caveat = json!({"templates": ["newsletter"]});
+    let send_email_as_alice: Capability = EMAIL_SEMANTICS
+        .parse("mailto:alice@email.com", "email/send", Some(&caveat))
+        .unwrap()
+        .into();
+
+    let (proof_ucan_cid, proof_token) = make_proof(
+        &identities.alice_key,
+        identities.bob_did.clone(),
+        UcanOptions {
+            capabilities: vec![send_email_as_alice],
+            ..UcanOptions::for_version(version)
+        },
+    )
+    .await?;
+
+    let escalated_caveat = json!({"templates": ["marketing"]});
+    let send_email_as_alice_escalated: Capability = EMAIL_SEMANTICS
+        .parse(
+            "mailto:alice@email.com",
+            "email/send",
+            Some(&escalated_caveat),
+        )
+        .unwrap()
+        .into();
+
+    make_fixture(
+        String::from("UCAN escalates by adding a different caveat"),
+        &identities.bob_key,
+        identities.mallory_did.clone(),
+        UcanOptions {
+            capabilities: vec![send_email_as_alice_escalated],
+            proofs: vec![proof_ucan_cid],
+            ..UcanOptions::for_version(version)
+        },
+        vec![proof_token],
+        identities.alice_did.clone(),
+    )
+    .await
+}
+
+// bob's proof claims a broader caveat than alice granted, and mallory's
+// token claims exactly what bob's proof does. Checked against the leaf
+// proof alone it is valid, so the chain must be walked back to alice's
+// proof to catch the escalation.
+async fn caveats_escalate_after_two_hops(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<DisproveFixture> {
+    let caveat = json!({"templates": ["newsletter"]});
+    let send_newsletter_as_alice: Capability = EMAIL_SEMANTICS
+        .parse("mailto:alice@email.com", "email/send", Some(&caveat))
+        .unwrap()
+        .into();
+
+    let (alice_proof_ucan_cid, alice_proof_token) = make_proof(
+        &identities.alice_key,
+        identities.bob_did.clone(),
+        UcanOptions {
+            capabilities: vec![send_newsletter_as_alice],
+            ..UcanOptions::for_version(version)
+        },
+    )
+    .await?;
+
+    let escalated_caveat = json!({"templates": ["newsletter", "marketing"]});
+    let send_email_as_alice_escalated: Capability = EMAIL_SEMANTICS
+        .parse(
+            "mailto:alice@email.com",
+            "email/send",
+            Some(&escalated_caveat),
+        )
+        .unwrap()
+        .into();
+
+    let (bob_proof_ucan_cid, bob_proof_token) = make_proof(
+        &identities.bob_key,
+        identities.mallory_did.clone(),
+        UcanOptions {
+            capabilities: vec![send_email_as_alice_escalated.clone()],
+            proofs: vec![alice_proof_ucan_cid],
+            ..UcanOptions::for_version(version)
+        },
+    )
+    .await?;
+
+    make_fixture(
+        String::from("UCAN escalates caveats delegated across two hops"),
+        &identities.mallory_key,
+        identities.victor_did.clone(),
+        UcanOptions {
+            capabilities: vec![send_email_as_alice_escalated],
+            proofs: vec![bob_proof_ucan_cid],
+            ..UcanOptions::for_version(version)
+        },
+        vec![alice_proof_token, bob_proof_token],
+        identities.alice_did.clone(),
+    )
+    .await
+}
@@ -0,0 +1,126 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Error codes that refute fixtures assert, serialized as their `code`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum RefuteError {
+    Expired,
+    NotReady,
+    TimeBoundsViolation,
+    Malformed,
+    MalformedEncoding,
+    MissingField,
+    IncorrectType,
+    MalformedCapability,
+    IncorrectProofs,
+    MissingProof,
+    UnsupportedDid,
+    InvalidSignature,
+    InvalidDelegation,
+    VersionMismatch,
+}
+
+impl RefuteError {
+    pub const ALL: &'static [RefuteError] = &[
+        RefuteError::Expired,
+        RefuteError::NotReady,
+        RefuteError::TimeBoundsViolation,
+        RefuteError::Malformed,
+        RefuteError::MalformedEncoding,
+        RefuteError::MissingField,
+        RefuteError::IncorrectType,
+        RefuteError::MalformedCapability,
+        RefuteError::IncorrectProofs,
+        RefuteError::MissingProof,
+        RefuteError::UnsupportedDid,
+        RefuteError::InvalidSignature,
+        RefuteError::InvalidDelegation,
+        RefuteError::VersionMismatch,
+    ];
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            RefuteError::Expired => "expired",
+            RefuteError::NotReady => "notReady",
+            RefuteError::TimeBoundsViolation => "timeBoundsViolation",
+            RefuteError::Malformed => "malformed",
+            RefuteError::MalformedEncoding => "malformedEncoding",
+            RefuteError::MissingField => "missingField",
+            RefuteError::IncorrectType => "incorrectType",
+            RefuteError::MalformedCapability => "malformedCapability",
+            RefuteError::IncorrectProofs => "incorrectProofs",
+            RefuteError::MissingProof => "missingProof",
+            RefuteError::UnsupportedDid => "unsupportedDid",
+            RefuteError::InvalidSignature => "invalidSignature",
+            RefuteError::InvalidDelegation => "invalidDelegation",
+            RefuteError::VersionMismatch => "versionMismatch",
+        }
+    }
+
+    /// The error with this `code`, if there is one
+    pub fn from_code(code: &str) -> Option<RefuteError> {
+        RefuteError::ALL
+            .iter()
+            .copied()
+            .find(|error| error.code() == code)
+    }
+
+    /// Whether the error can leave a token that does not decode as a UCAN,
+    /// so fixtures expecting it are not held to decoding
+    pub fn is_structural(&self) -> bool {
+        matches!(
+            self,
+            RefuteError::Malformed
+                | RefuteError::MalformedEncoding
+                | RefuteError::MissingField
+                | RefuteError::IncorrectType
+                | RefuteError::MalformedCapability
+                | RefuteError::IncorrectProofs
+        )
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            RefuteError::Expired => "The UCAN expiration is in the past",
+            RefuteError::NotReady => "The UCAN not before time is in the future",
+            RefuteError::TimeBoundsViolation => {
+                "The UCAN time bounds are empty or not contained by its proofs time bounds"
+            }
+            RefuteError::Malformed => "The token cannot be parsed as a JWT",
+            RefuteError::MalformedEncoding => "A token segment is not unpadded base64url",
+            RefuteError::MissingField => "A required header or payload field is missing",
+            RefuteError::IncorrectType => "A header or payload field has the wrong type or format",
+            RefuteError::MalformedCapability => {
+                "A capability resource or ability does not follow the capability grammar"
+            }
+            RefuteError::IncorrectProofs => "The prf field does not contain CIDs",
+            RefuteError::MissingProof => "A prf CID does not match any proof provided",
+            RefuteError::UnsupportedDid => "A well formed DID uses a method that is not supported",
+            RefuteError::InvalidSignature => "The signature does not verify with the issuer key",
+            RefuteError::InvalidDelegation => {
+                "A claimed capability or issuer is not supported by the proofs, \
+                 whatever order the capabilities and proofs are listed in"
+            }
+            RefuteError::VersionMismatch => "A proof has a different UCAN version",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ErrorCode {
+    code: String,
+    description: String,
+}
+
+/// Every error code with a short description, for implementers mapping
+/// these codes to their own errors
+pub fn error_codes() -> Vec<ErrorCode> {
+    RefuteError::ALL
+        .iter()
+        .map(|error| ErrorCode {
+            code: error.code().into(),
+            description: error.description().into(),
+        })
+        .collect()
+}
@@ -0,0 +1,118 @@
+use super::{errors::RefuteError, refute::RefuteFixture, verify::VerifyFixture};
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::{fs, path::Path, str::FromStr};
+use ucan::Ucan;
+
+/// A fixture imported from a bug report, in the format
+///
+/// ```json
+/// {
+///   "name": "UCAN from issue 42",
+///   "task": "refute",
+///   "token": "eyJhbGciOi...",
+///   "proofs": ["eyJhbGciOi..."],
+///   "errors": ["invalidDelegation"]
+/// }
+/// ```
+///
+/// `proofs` may be omitted, and `errors` is required for refute fixtures only.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ImportedFixture {
+    name: String,
+    task: String,
+    token: String,
+    #[serde(default)]
+    proofs: Vec<String>,
+    #[serde(default)]
+    errors: Vec<String>,
+}
+
+#[derive(Debug, Default)]
+pub struct ImportedFixtures {
+    pub verify: Vec<VerifyFixture>,
+    pub refute: Vec<RefuteFixture>,
+}
+
+/// Load every `.json` file in a directory, each holding an array of
+/// imported fixtures. A missing directory imports nothing.
+pub fn load_dir(dir: &Path) -> Result<ImportedFixtures> {
+    let mut imported = ImportedFixtures::default();
+
+    if !dir.exists() {
+        return Ok(imported);
+    }
+
+    let mut paths: Vec<_> = fs::read_dir(dir)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<_, _>>()?;
+    paths.retain(|path| {
+        path.extension()
+            .map_or(false, |extension| extension == "json")
+    });
+    paths.sort();
+
+    for path in paths {
+        let fixtures: Vec<ImportedFixture> = serde_json::from_str(&fs::read_to_string(&path)?)
+            .map_err(|err| anyhow!("Could not parse {}: {}", path.display(), err))?;
+
+        for fixture in fixtures {
+            fold(&mut imported, fixture)?;
+        }
+    }
+
+    Ok(imported)
+}
+
+fn fold(imported: &mut ImportedFixtures, fixture: ImportedFixture) -> Result<()> {
+    let ImportedFixture {
+        name,
+        task,
+        token,
+        proofs,
+        errors,
+    } = fixture;
+
+    if name.is_empty() {
+        return Err(anyhow!("Imported fixture has an empty name"));
+    }
+
+    match task.as_str() {
+        "verify" => {
+            if !errors.is_empty() {
+                return Err(anyhow!("Imported verify fixture has errors: {}", name));
+            }
+
+            for proof in proofs.iter() {
+                Ucan::from_str(proof)
+                    .map_err(|err| anyhow!("Imported proof does not decode: {}: {}", name, err))?;
+            }
+
+            let fixture = super::verify::imported(name.clone(), token, proofs)
+                .map_err(|err| anyhow!("Imported token does not decode: {}: {}", name, err))?;
+            imported.verify.push(fixture);
+        }
+
+        "refute" => {
+            if errors.is_empty() {
+                return Err(anyhow!("Imported refute fixture has no errors: {}", name));
+            }
+
+            let errors = errors
+                .iter()
+                .map(|code| {
+                    RefuteError::from_code(code)
+                        .ok_or_else(|| anyhow!("Unknown error code {} in: {}", code, name))
+                })
+                .collect::<Result<Vec<RefuteError>>>()?;
+
+            imported
+                .refute
+                .push(super::refute::imported(name, token, proofs, errors));
+        }
+
+        _ => return Err(anyhow!("Unsupported task {} in: {}", task, name)),
+    }
+
+    Ok(())
+}
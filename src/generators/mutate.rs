@@ -1,28 +1,35 @@
+use super::encode_part;
+use anyhow::{anyhow, bail, ensure, Context, Result};
 use base64::{engine::general_purpose, Engine as _};
-use serde_json::{Map, Value};
+use serde::Deserialize;
+use serde_json::{Deserializer, Map, Value};
+use ucan::crypto::KeyMaterial;
 use ucan_key_support::ed25519::Ed25519KeyMaterial;
 
-pub fn remove_field(token: &str, part: &str, field: &str, signer: Ed25519KeyMaterial) -> String {
-    let parts: Vec<&str> = token.split('.').collect();
+pub fn remove_field(
+    token: &str,
+    part: &str,
+    field: &str,
+    signer: Ed25519KeyMaterial,
+) -> Result<String> {
+    let parts = segments(token, 2)?;
 
     match part {
         "header" => {
-            let mut header_map = part_to_map(parts[0]);
+            let mut header_map = part_to_map(parts[0])?;
             header_map.remove(field);
 
             sign(map_to_part(header_map), String::from(parts[1]), signer)
         }
 
         "payload" => {
-            let mut payload_map = part_to_map(parts[1]);
+            let mut payload_map = part_to_map(parts[1])?;
             payload_map.remove(field);
 
             sign(String::from(parts[0]), map_to_part(payload_map), signer)
         }
 
-        _ => {
-            panic!()
-        }
+        _ => unknown_part(part),
     }
 }
 
@@ -32,46 +39,314 @@ pub fn mutate_field(
     field: &str,
     value: Value,
     signer: Ed25519KeyMaterial,
-) -> String {
-    let parts: Vec<&str> = token.split('.').collect();
+) -> Result<String> {
+    mutate_path(token, part, &[field], value, signer)
+}
+
+/// Replace the value at `path` in the header or payload, and re-sign. Each
+/// step is an object key, or an index into an array. The value must
+/// already exist, so a typo in the path is an error.
+pub fn mutate_path(
+    token: &str,
+    part: &str,
+    path: &[&str],
+    value: Value,
+    signer: Ed25519KeyMaterial,
+) -> Result<String> {
+    let parts = segments(token, 2)?;
 
     match part {
         "header" => {
-            let mut header_map = part_to_map(parts[0]);
-            *header_map.get_mut(field).unwrap() = value;
+            let mut header = Value::Object(part_to_map(parts[0])?);
+            *value_at_path(&mut header, part, path)? = value;
+
+            sign(encode_part(&header), String::from(parts[1]), signer)
+        }
+
+        "payload" => {
+            let mut payload = Value::Object(part_to_map(parts[1])?);
+            *value_at_path(&mut payload, part, path)? = value;
+
+            sign(String::from(parts[0]), encode_part(&payload), signer)
+        }
+
+        _ => unknown_part(part),
+    }
+}
+
+fn value_at_path<'a>(root: &'a mut Value, part: &str, path: &[&str]) -> Result<&'a mut Value> {
+    path.iter()
+        .enumerate()
+        .try_fold(root, |value, (depth, step)| {
+            let next = match value {
+                Value::Object(map) => map.get_mut(*step),
+                Value::Array(items) => step
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(move |index| items.get_mut(index)),
+                _ => None,
+            };
+
+            next.ok_or_else(|| {
+                anyhow!(
+                    "No {} field at {} to mutate",
+                    part,
+                    path[..=depth].join(".")
+                )
+            })
+        })
+}
+
+/// Add a field the header or payload does not already have, and re-sign
+pub fn add_field(
+    token: &str,
+    part: &str,
+    field: &str,
+    value: Value,
+    signer: Ed25519KeyMaterial,
+) -> Result<String> {
+    let parts = segments(token, 2)?;
+
+    match part {
+        "header" => {
+            let mut header_map = part_to_map(parts[0])?;
+            ensure!(
+                !header_map.contains_key(field),
+                "The header already has a {field} field"
+            );
+            header_map.insert(field.to_string(), value);
 
             sign(map_to_part(header_map), String::from(parts[1]), signer)
         }
 
         "payload" => {
-            let mut payload_map = part_to_map(parts[1]);
-            *payload_map.get_mut(field).unwrap() = value;
+            let mut payload_map = part_to_map(parts[1])?;
+            ensure!(
+                !payload_map.contains_key(field),
+                "The payload already has a {field} field"
+            );
+            payload_map.insert(field.to_string(), value);
 
             sign(String::from(parts[0]), map_to_part(payload_map), signer)
         }
 
-        _ => {
-            panic!()
+        _ => unknown_part(part),
+    }
+}
+
+/// Re-sign a token's header and payload, unchanged, with another key. The
+/// `iss` field still names the original issuer.
+pub fn resign_with(token: &str, signer: Ed25519KeyMaterial) -> Result<String> {
+    let parts = segments(token, 2)?;
+
+    sign(String::from(parts[0]), String::from(parts[1]), signer)
+}
+
+/// Re-sign a token with its payload keys in reverse order. The claims are
+/// unchanged, but the payload bytes, and so the CID, differ.
+pub fn reverse_payload_keys(token: &str, signer: Ed25519KeyMaterial) -> Result<String> {
+    let parts = segments(token, 2)?;
+    let payload_map: Map<String, Value> = part_to_map(parts[1])?.into_iter().rev().collect();
+
+    sign(String::from(parts[0]), map_to_part(payload_map), signer)
+}
+
+/// Re-sign a token with the keys of every header and payload object sorted
+/// by their UTF-8 bytes, serialized as compact JSON. The claims are
+/// unchanged, and any implementation sorting the same way signs the same
+/// bytes. Unlike the helpers above, this signs with any key type.
+pub async fn sort_keys<K: KeyMaterial>(token: &str, signer: &K) -> Result<String> {
+    let parts = segments(token, 2)?;
+    let header = encode_part(&sorted(Value::Object(part_to_map(parts[0])?)));
+    let payload = encode_part(&sorted(Value::Object(part_to_map(parts[1])?)));
+
+    let raw_signature = signer
+        .sign(format!("{header}.{payload}").as_bytes())
+        .await?;
+    let signature = general_purpose::URL_SAFE_NO_PAD.encode(raw_signature);
+
+    Ok(format!("{header}.{payload}.{signature}"))
+}
+
+fn sorted(value: Value) -> Value {
+    match value {
+        Value::Object(map) => {
+            let mut entries: Vec<(String, Value)> = map.into_iter().collect();
+            entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+            Value::Object(
+                entries
+                    .into_iter()
+                    .map(|(key, value)| (key, sorted(value)))
+                    .collect(),
+            )
         }
+        Value::Array(values) => Value::Array(values.into_iter().map(sorted).collect()),
+        value => value,
+    }
+}
+
+/// Insert `text` into the middle of the header, payload, or signature
+/// segment. A corrupted header or payload is re-signed, so the signature
+/// is valid over the segment bytes as sent.
+pub fn corrupt_part(
+    token: &str,
+    part: &str,
+    text: &str,
+    signer: Ed25519KeyMaterial,
+) -> Result<String> {
+    let parts = segments(token, 3)?;
+
+    let corrupt = |segment: &str| {
+        let (start, end) = segment.split_at(segment.len() / 2);
+        format!("{start}{text}{end}")
+    };
+
+    match part {
+        "header" => sign(corrupt(parts[0]), String::from(parts[1]), signer),
+        "payload" => sign(String::from(parts[0]), corrupt(parts[1]), signer),
+        "signature" => Ok(format!("{}.{}.{}", parts[0], parts[1], corrupt(parts[2]))),
+        _ => unknown_part(part),
     }
 }
 
-fn part_to_map(part: &str) -> Map<String, Value> {
-    let part_vec = general_purpose::URL_SAFE_NO_PAD.decode(part).unwrap();
-    let part_json_string = String::from_utf8(part_vec).unwrap();
-    serde_json::from_str(&part_json_string[..]).unwrap()
+/// Drop the signature segment, leaving `header.payload`
+pub fn drop_signature(token: &str) -> Result<String> {
+    let parts = segments(token, 3)?;
+
+    Ok(format!("{}.{}", parts[0], parts[1]))
+}
+
+/// Append a fourth segment after the signature
+pub fn append_segment(token: &str, segment: &str) -> String {
+    format!("{token}.{segment}")
+}
+
+/// Re-sign a token with `key` repeated at the start of the payload `field`
+/// object, holding `value`. A `Map` keeps one entry per key, so the raw
+/// payload JSON is edited instead.
+pub fn duplicate_payload_key(
+    token: &str,
+    field: &str,
+    key: &str,
+    value: Value,
+    signer: Ed25519KeyMaterial,
+) -> Result<String> {
+    let parts = segments(token, 2)?;
+    let payload = raw_part(token, "payload")?;
+
+    let opening = format!("\"{field}\":{{");
+    let start = payload
+        .find(&opening)
+        .ok_or_else(|| anyhow!("No payload field {field} object to duplicate a key in"))?
+        + opening.len()
+        - 1;
+    // Only the keys of the field's own object count, not those of any other
+    // object in the payload
+    let object = Map::<String, Value>::deserialize(&mut Deserializer::from_str(&payload[start..]))
+        .with_context(|| format!("Payload field {field} is not an object"))?;
+    ensure!(
+        object.contains_key(key),
+        "No payload field {field} with key {key} to duplicate"
+    );
+
+    let key = Value::from(key);
+    let payload = format!(
+        "{}{key}:{value},{}",
+        &payload[..=start],
+        &payload[start + 1..]
+    );
+
+    sign(
+        String::from(parts[0]),
+        general_purpose::URL_SAFE_NO_PAD.encode(payload),
+        signer,
+    )
+}
+
+/// Re-sign a token with the first `from` in its payload JSON replaced by
+/// `to`. A `Map` keeps one entry per key and only nests so deep, so the raw
+/// JSON is edited for content like duplicate keys.
+pub fn replace_in_payload(
+    token: &str,
+    from: &str,
+    to: &str,
+    signer: Ed25519KeyMaterial,
+) -> Result<String> {
+    let parts = segments(token, 2)?;
+    let payload = raw_part(token, "payload")?;
+    ensure!(
+        payload.contains(from),
+        "No {from} in the payload to replace"
+    );
+
+    sign(
+        String::from(parts[0]),
+        general_purpose::URL_SAFE_NO_PAD.encode(payload.replacen(from, to, 1)),
+        signer,
+    )
+}
+
+/// Sign header and payload JSON exactly as written. Use this for content
+/// that a `Map` cannot represent, like duplicate keys.
+pub fn build_raw_token(header: &str, payload: &str, signer: Ed25519KeyMaterial) -> Result<String> {
+    sign(
+        general_purpose::URL_SAFE_NO_PAD.encode(header),
+        general_purpose::URL_SAFE_NO_PAD.encode(payload),
+        signer,
+    )
+}
+
+/// Decode the header or payload of a token to its JSON string
+pub fn raw_part(token: &str, part: &str) -> Result<String> {
+    let parts = segments(token, 2)?;
+
+    let encoded = match part {
+        "header" => parts[0],
+        "payload" => parts[1],
+        _ => return unknown_part(part),
+    };
+
+    Ok(String::from_utf8(
+        general_purpose::URL_SAFE_NO_PAD.decode(encoded)?,
+    )?)
+}
+
+/// The dot separated segments of a token, which must have at least `count`
+fn segments(token: &str, count: usize) -> Result<Vec<&str>> {
+    let parts: Vec<&str> = token.split('.').collect();
+    ensure!(
+        parts.len() >= count,
+        "Token has {} segments, expected at least {}",
+        parts.len(),
+        count
+    );
+
+    Ok(parts)
+}
+
+fn unknown_part<T>(part: &str) -> Result<T> {
+    bail!("No token part named {part}")
+}
+
+fn part_to_map(part: &str) -> Result<Map<String, Value>> {
+    let part_vec = general_purpose::URL_SAFE_NO_PAD.decode(part)?;
+    let part_json_string = String::from_utf8(part_vec)?;
+
+    Ok(serde_json::from_str(&part_json_string[..])?)
 }
 
 fn map_to_part(map: Map<String, Value>) -> String {
-    let json_string = Value::Object(map).to_string();
-    general_purpose::URL_SAFE_NO_PAD.encode(json_string)
+    encode_part(&Value::Object(map))
 }
 
-fn sign(header: String, payload: String, signer: Ed25519KeyMaterial) -> String {
-    let private_key = signer.1.unwrap();
+fn sign(header: String, payload: String, signer: Ed25519KeyMaterial) -> Result<String> {
+    let private_key = signer
+        .1
+        .ok_or_else(|| anyhow!("No private key; cannot sign data"))?;
     let data_to_sign = format!("{header}.{payload}").as_bytes().to_vec();
     let raw_signature: [u8; 64] = private_key.sign(data_to_sign.as_slice()).into();
     let signature: String = base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(raw_signature);
 
-    format!("{header}.{payload}.{signature}")
+    Ok(format!("{header}.{payload}.{signature}"))
 }
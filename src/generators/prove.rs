@@ -0,0 +1,264 @@
+use super::{
+    assertions::{ucan_to_assertions, UcanAssertions},
+    categorize, make_proof, sign_with_options, with_decoded, Conformance, Fixture, TokenFixture,
+    UcanOptions,
+};
+use crate::{
+    capabilities::{EmailSemantics, HttpSemantics},
+    identities::Identities,
+};
+use anyhow::{Context, Result};
+use futures::{future::try_join_all, FutureExt};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::rc::Rc;
+use ucan::{
+    capability::{Capability, CapabilitySemantics},
+    crypto::KeyMaterial,
+    Ucan,
+};
+use ucan_key_support::ed25519::Ed25519KeyMaterial;
+
+/// A fixture asserting that a token grants `capabilities`, originating
+/// from `issuer`, at the unix timestamp `time`
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ProveFixture {
+    pub name: String,
+    pub task: String,
+    pub category: String,
+    pub conformance: Conformance,
+    pub inputs: Inputs,
+    pub assertions: UcanAssertions,
+    #[schemars(with = "Vec<Value>")]
+    pub capabilities: Vec<Capability>,
+    pub issuer: String,
+    pub time: u64,
+}
+
+impl ProveFixture {
+    fn new(
+        name: String,
+        inputs: Inputs,
+        assertions: UcanAssertions,
+        capabilities: Vec<Capability>,
+        issuer: String,
+    ) -> Self {
+        ProveFixture {
+            name,
+            task: "prove".to_string(),
+            category: String::new(),
+            conformance: Conformance::Must,
+            inputs,
+            assertions,
+            capabilities,
+            issuer,
+            time: PROVE_TIME,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl Fixture for ProveFixture {
+    fn category_mut(&mut self) -> &mut String {
+        &mut self.category
+    }
+}
+
+impl TokenFixture for ProveFixture {
+    fn token_and_assertions(&mut self) -> (&str, &mut UcanAssertions) {
+        (self.inputs.token.as_str(), &mut self.assertions)
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct Inputs {
+    pub token: String,
+    pub proofs: Vec<String>,
+}
+
+const EMAIL_SEMANTICS: EmailSemantics = EmailSemantics {};
+const HTTP_SEMANTICS: HttpSemantics = HttpSemantics {};
+
+/// Fixtures prove capabilities at this time. Their tokens have no time
+/// bounds, so any time would do, but a fixed one keeps output stable.
+const PROVE_TIME: u64 = 1_700_000_000;
+
+// GENERATE
+
+pub async fn generate(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<Vec<ProveFixture>> {
+    let fixtures: Vec<ProveFixture> = [categorize(
+        "delegation",
+        try_join_all(vec![
+            has_delegated_capability(identities.clone(), version).boxed_local(),
+            merges_delegated_capabilities(identities.clone(), version).boxed_local(),
+            http_any_grants_method(identities.clone(), version).boxed_local(),
+        ])
+        .await?,
+    )]
+    .into_iter()
+    .flatten()
+    .collect();
+
+    Ok(with_decoded(fixtures))
+}
+
+/// Sign a token claiming `options.capabilities` and prove those same
+/// capabilities from `root_issuer`
+async fn make_fixture<K: KeyMaterial>(
+    name: String,
+    issuer: &K,
+    audience: String,
+    options: UcanOptions,
+    proofs: Vec<String>,
+    root_issuer: String,
+) -> Result<ProveFixture> {
+    let capabilities = options.capabilities.clone();
+    let ucan = sign_with_options(issuer, audience.clone(), options)
+        .await
+        .with_context(|| format!("Could not sign fixture: {}", name))?;
+
+    let inputs = Inputs {
+        token: Ucan::encode(&ucan)?,
+        proofs,
+    };
+    let assertions = ucan_to_assertions(ucan);
+
+    Ok(ProveFixture::new(
+        name,
+        inputs,
+        assertions,
+        capabilities,
+        root_issuer,
+    ))
+}
+
+// DELEGATION
+
+async fn has_delegated_capability(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<ProveFixture> {
+    let send_email_as_alice: Capability = EMAIL_SEMANTICS
+        .parse("mailto:alice@email.com", "email/send", None)
+        .unwrap()
+        .into();
+
+    let (proof_ucan_cid, proof_token) = make_proof(
+        &identities.alice_key,
+        identities.bob_did.clone(),
+        UcanOptions {
+            capabilities: vec![send_email_as_alice.clone()],
+            ..UcanOptions::for_version(version)
+        },
+    )
+    .await?;
+
+    make_fixture(
+        String::from("UCAN has a delegated capability"),
+        &identities.bob_key,
+        identities.mallory_did.clone(),
+        UcanOptions {
+            capabilities: vec![send_email_as_alice],
+            proofs: vec![proof_ucan_cid],
+            ..UcanOptions::for_version(version)
+        },
+        vec![proof_token],
+        identities.alice_did.clone(),
+    )
+    .await
+}
+
+async fn merges_delegated_capabilities(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<ProveFixture> {
+    let send_email_as_alice: Capability = EMAIL_SEMANTICS
+        .parse("mailto:alice@email.com", "email/send", None)
+        .unwrap()
+        .into();
+
+    let (alice_proof_ucan_cid, alice_proof_token) = make_proof(
+        &identities.alice_key,
+        identities.bob_did.clone(),
+        UcanOptions {
+            capabilities: vec![send_email_as_alice.clone()],
+            ..UcanOptions::for_version(version)
+        },
+    )
+    .await?;
+
+    let send_email_as_marketing: Capability = EMAIL_SEMANTICS
+        .parse("mailto:marketing@email.com", "email/send", None)
+        .unwrap()
+        .into();
+
+    let (marketing_proof_ucan_cid, marketing_proof_token) = make_proof(
+        &identities.alice_key,
+        identities.bob_did.clone(),
+        UcanOptions {
+            capabilities: vec![send_email_as_marketing.clone()],
+            ..UcanOptions::for_version(version)
+        },
+    )
+    .await?;
+
+    make_fixture(
+        String::from("UCAN merges delegated capabilities"),
+        &identities.bob_key,
+        identities.mallory_did.clone(),
+        UcanOptions {
+            capabilities: vec![send_email_as_alice, send_email_as_marketing],
+            proofs: vec![alice_proof_ucan_cid, marketing_proof_ucan_cid],
+            ..UcanOptions::for_version(version)
+        },
+        vec![alice_proof_token, marketing_proof_token],
+        identities.alice_did.clone(),
+    )
+    .await
+}
+
+// http/* subsumes every method, so a proof of it grants http/GET
+async fn http_any_grants_method(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<ProveFixture> {
+    let any_method: Capability = HTTP_SEMANTICS
+        .parse("https://example.com/api", "http/*", None)
+        .unwrap()
+        .into();
+    let get_api: Capability = HTTP_SEMANTICS
+        .parse("https://example.com/api", "http/GET", None)
+        .unwrap()
+        .into();
+
+    let (proof_ucan_cid, proof_token) = make_proof(
+        &identities.alice_key,
+        identities.bob_did.clone(),
+        UcanOptions {
+            capabilities: vec![any_method],
+            ..UcanOptions::for_version(version)
+        },
+    )
+    .await?;
+
+    make_fixture(
+        String::from("UCAN has a method delegated by an http/* proof"),
+        &identities.bob_key,
+        identities.mallory_did.clone(),
+        UcanOptions {
+            capabilities: vec![get_api],
+            proofs: vec![proof_ucan_cid],
+            ..UcanOptions::for_version(version)
+        },
+        vec![proof_token],
+        identities.alice_did.clone(),
+    )
+    .await
+}
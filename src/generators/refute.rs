@@ -1,47 +1,103 @@
 use super::{
-    assertions::{ucan_to_assertions, UcanAssertions},
+    assertions::{empty_assertions, ucan_to_assertions, UcanAssertions},
+    categorize, encode_payload,
+    errors::RefuteError,
     make_proof,
-    mutate::{mutate_field, remove_field},
-    UcanOptions,
+    mutate::{
+        append_segment, build_raw_token, corrupt_part, drop_signature, duplicate_payload_key,
+        mutate_field, mutate_path, raw_part, remove_field, resign_with,
+    },
+    sign_with_options, token_to_cid, with_decoded, Conformance, Fixture, TokenFixture, UcanOptions,
+    CLOCK_SKEW_SECONDS, CLOCK_SKEW_TIME,
 };
-use crate::{capabilities::EmailSemantics, identities::Identities};
-use anyhow::Result;
+use crate::{
+    capabilities::{
+        AbilityHierarchy, EmailSemantics, HierarchySemantics, PathSemantics, WnfsSemantics,
+        HIERARCHY_RESOURCE,
+    },
+    identities::Identities,
+};
+use anyhow::{Context, Result};
+use futures::{future::try_join_all, FutureExt};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use std::collections::BTreeMap;
-use std::{collections::HashMap, rc::Rc};
+use std::{rc::Rc, str::FromStr};
 use ucan::{
-    builder::Signable,
     capability::{Capability, CapabilitySemantics},
     Ucan,
 };
 use ucan_key_support::ed25519::Ed25519KeyMaterial;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
 pub struct RefuteFixture {
-    name: String,
-    task: String,
-    inputs: Inputs,
-    assertions: UcanAssertions,
-    errors: Vec<String>,
+    pub name: String,
+    pub task: String,
+    pub category: String,
+    pub conformance: Conformance,
+    pub inputs: Inputs,
+    pub assertions: UcanAssertions,
+    pub errors: Vec<RefuteError>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub clock_skew_seconds: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time: Option<u64>,
 }
 
 impl RefuteFixture {
-    fn new(name: String, inputs: Inputs, assertions: UcanAssertions, errors: Vec<String>) -> Self {
+    fn new(
+        name: String,
+        inputs: Inputs,
+        assertions: UcanAssertions,
+        errors: Vec<RefuteError>,
+    ) -> Self {
         RefuteFixture {
             name,
             task: "refute".to_string(),
+            category: String::new(),
+            conformance: Conformance::Must,
             inputs,
             assertions,
             errors,
+            clock_skew_seconds: None,
+            time: None,
         }
     }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn with_conformance(mut self, conformance: Conformance) -> Self {
+        self.conformance = conformance;
+        self
+    }
+
+    /// Evaluate at `CLOCK_SKEW_TIME`, tolerating `CLOCK_SKEW_SECONDS`
+    fn with_clock_skew(mut self) -> Self {
+        self.clock_skew_seconds = Some(CLOCK_SKEW_SECONDS);
+        self.time = Some(CLOCK_SKEW_TIME);
+        self
+    }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-struct Inputs {
-    token: String,
-    proofs: HashMap<String, String>,
+impl Fixture for RefuteFixture {
+    fn category_mut(&mut self) -> &mut String {
+        &mut self.category
+    }
+}
+
+impl TokenFixture for RefuteFixture {
+    fn token_and_assertions(&mut self) -> (&str, &mut UcanAssertions) {
+        (self.inputs.token.as_str(), &mut self.assertions)
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct Inputs {
+    pub token: String,
+    pub proofs: Vec<String>,
 }
 
 impl Inputs {
@@ -51,55 +107,144 @@ impl Inputs {
 }
 
 const EMAIL_SEMANTICS: EmailSemantics = EmailSemantics {};
+const PATH_SEMANTICS: PathSemantics = PathSemantics {};
+const WNFS_SEMANTICS: WnfsSemantics = WnfsSemantics {};
+
+/// Nesting depth of the deeply nested facts fixture, well past the limits
+/// of common recursive JSON parsers
+const NESTED_FACTS_DEPTH: usize = 10_000;
 
 // GENERATE
 
-pub async fn generate() -> Result<Vec<RefuteFixture>> {
-    let identities = Rc::new(Identities::new().await);
-
-    let fixtures: Vec<RefuteFixture> = vec![
-        // Time bounds
-        expired(identities.clone()).await,
-        not_ready(identities.clone()).await,
-        expires_after_proofs(identities.clone()).await,
-        ready_before_proofs(identities.clone()).await,
-        // Encoding
-
-        // Missing fields
-        missing_type(identities.clone()).await,
-        missing_algorithm(identities.clone()).await,
-        missing_version(identities.clone()).await,
-        missing_issuer(identities.clone()).await,
-        missing_audience(identities.clone()).await,
-        missing_expiration(identities.clone()).await,
-        missing_capabilities(identities.clone()).await,
-        // Invalid fields
-        invalid_algorithm(identities.clone()).await,
-        invalid_type(identities.clone()).await,
-        invalid_type_not_jwt(identities.clone()).await,
-        invalid_version(identities.clone()).await,
-        invalid_version_not_semantic(identities.clone()).await,
-        invalid_issuer(identities.clone()).await,
-        invalid_audience(identities.clone()).await,
-        invalid_not_before(identities.clone()).await,
-        invalid_expiration(identities.clone()).await,
-        invalid_nonce(identities.clone()).await,
-        invalid_facts(identities.clone()).await,
-        invalid_capabilities(identities.clone()).await,
-        invalid_capabilities_ability(identities.clone()).await,
-        invalid_capabilities_caveats(identities.clone()).await,
-        invalid_capabilities_caveats_empty(identities.clone()).await,
-        invalid_proofs(identities.clone()).await,
-        invalid_proof_cids(identities.clone()).await,
-        // Delegation
-        issuer_does_not_match_proof_audience(identities.clone()).await,
-        claimed_capability_not_delegated(identities.clone()).await,
-        caveats_escalate_with_new_caveat(identities.clone()).await,
-        caveats_escalate_to_no_caveats(identities.clone()).await,
-        caveats_escalate_with_different_caveat(identities.clone()).await,
-    ];
-
-    Ok(fixtures)
+pub async fn generate(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<Vec<RefuteFixture>> {
+    let fixtures: Vec<RefuteFixture> = [
+        categorize(
+            "time",
+            try_join_all(vec![
+                expired(identities.clone(), version).boxed_local(),
+                not_ready(identities.clone(), version).boxed_local(),
+                not_before_after_expiration(identities.clone(), version).boxed_local(),
+                expires_after_proofs(identities.clone(), version).boxed_local(),
+                proof_expired(identities.clone(), version).boxed_local(),
+                ready_before_proofs(identities.clone(), version).boxed_local(),
+                capability_ok_time_escalates(identities.clone(), version).boxed_local(),
+                expired_outside_clock_skew(identities.clone(), version).boxed_local(),
+                not_ready_outside_clock_skew(identities.clone(), version).boxed_local(),
+            ])
+            .await?,
+        ),
+        categorize(
+            "encoding",
+            try_join_all(vec![
+                trailing_signature_data(identities.clone(), version).boxed_local(),
+                header_not_base64(identities.clone(), version).boxed_local(),
+                payload_inner_padding(identities.clone(), version).boxed_local(),
+                signature_not_url_safe(identities.clone(), version).boxed_local(),
+                missing_signature_segment(identities.clone(), version).boxed_local(),
+                extra_segment(identities.clone(), version).boxed_local(),
+                deeply_nested_facts(identities.clone(), version).boxed_local(),
+            ])
+            .await?,
+        ),
+        categorize(
+            "missing",
+            try_join_all(vec![
+                missing_type(identities.clone(), version).boxed_local(),
+                missing_algorithm(identities.clone(), version).boxed_local(),
+                missing_version(identities.clone(), version).boxed_local(),
+                missing_issuer(identities.clone(), version).boxed_local(),
+                missing_audience(identities.clone(), version).boxed_local(),
+                missing_expiration(identities.clone(), version).boxed_local(),
+                missing_capabilities(identities.clone(), version).boxed_local(),
+            ])
+            .await?,
+        ),
+        categorize(
+            "invalid",
+            try_join_all(vec![
+                invalid_algorithm(identities.clone(), version).boxed_local(),
+                alg_signature_mismatch(identities.clone(), version).boxed_local(),
+                invalid_type(identities.clone(), version).boxed_local(),
+                invalid_type_not_jwt(identities.clone(), version).boxed_local(),
+                invalid_version(identities.clone(), version).boxed_local(),
+                invalid_version_not_semantic(identities.clone(), version).boxed_local(),
+                invalid_issuer(identities.clone(), version).boxed_local(),
+                unsupported_issuer_did_method(identities.clone(), version).boxed_local(),
+                invalid_audience(identities.clone(), version).boxed_local(),
+                invalid_not_before(identities.clone(), version).boxed_local(),
+                invalid_expiration(identities.clone(), version).boxed_local(),
+                invalid_nonce(identities.clone(), version).boxed_local(),
+                invalid_facts(identities.clone(), version).boxed_local(),
+                invalid_capabilities(identities.clone(), version).boxed_local(),
+                capabilities_is_array(identities.clone(), version).boxed_local(),
+                invalid_capabilities_ability(identities.clone(), version).boxed_local(),
+                invalid_capabilities_caveats(identities.clone(), version).boxed_local(),
+                invalid_capabilities_caveats_empty(identities.clone(), version).boxed_local(),
+                mixed_any_and_restricted_caveat(identities.clone(), version).boxed_local(),
+                duplicate_caveat_key(identities.clone(), version).boxed_local(),
+                ability_double_slash(identities.clone(), version).boxed_local(),
+                ability_not_namespaced(identities.clone(), version).boxed_local(),
+                ability_trailing_slash(identities.clone(), version).boxed_local(),
+                numeric_string_capability(identities.clone(), version).boxed_local(),
+                resource_not_uri(identities.clone(), version).boxed_local(),
+                duplicate_capability_resource(identities.clone(), version).boxed_local(),
+                invalid_proofs(identities.clone(), version).boxed_local(),
+                invalid_proof_cids(identities.clone(), version).boxed_local(),
+            ])
+            .await?,
+        ),
+        categorize(
+            "signature",
+            try_join_all(vec![
+                iss_key_does_not_verify(identities.clone(), version).boxed_local(),
+                signed_by_other_key(identities.clone(), version).boxed_local(),
+            ])
+            .await?,
+        ),
+        categorize(
+            "delegation",
+            try_join_all(vec![
+                issuer_does_not_match_proof_audience(identities.clone(), version).boxed_local(),
+                mixed_version_chain(identities.clone(), version).boxed_local(),
+                resource_subpath_claim(identities.clone(), version).boxed_local(),
+                wnfs_path_escalates(identities.clone(), version).boxed_local(),
+                dangling_proof_cid(identities.clone(), version).boxed_local(),
+            ])
+            .await?,
+        ),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+
+    Ok(with_decoded(fixtures))
+}
+
+pub async fn generate_for_hierarchy(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    hierarchy: &AbilityHierarchy,
+    version: &str,
+) -> Result<Vec<RefuteFixture>> {
+    let semantics = hierarchy.semantics();
+
+    let mut fixtures: Vec<RefuteFixture> = vec![];
+    for (ability, escalated) in hierarchy.escalations() {
+        fixtures.push(
+            escalates_ability(
+                identities.clone(),
+                &semantics,
+                &ability,
+                &escalated,
+                version,
+            )
+            .await?,
+        );
+    }
+
+    Ok(with_decoded(categorize("hierarchy", fixtures)))
 }
 
 async fn make_fixture(
@@ -107,72 +252,114 @@ async fn make_fixture(
     issuer: &Ed25519KeyMaterial,
     audience: String,
     options: UcanOptions,
-    proofs: HashMap<String, String>,
-    errors: Vec<String>,
-) -> RefuteFixture {
-    let signable = Signable {
-        issuer: &issuer.clone(),
-        audience: audience.clone(),
-        capabilities: options.capabilities,
-        expiration: options.expiration,
-        not_before: options.not_before,
-        facts: options.facts,
-        proofs: options.proofs,
-        add_nonce: options.add_nonce,
-    };
-    let ucan = signable.sign().await.unwrap();
+    proofs: Vec<String>,
+    errors: Vec<RefuteError>,
+) -> Result<RefuteFixture> {
+    let ucan = sign_with_options(issuer, audience.clone(), options)
+        .await
+        .with_context(|| format!("Could not sign fixture: {}", name))?;
 
     let inputs = Inputs {
-        token: Ucan::encode(&ucan).unwrap(),
+        token: Ucan::encode(&ucan)?,
         proofs,
     };
     let assertions = ucan_to_assertions(ucan);
 
-    RefuteFixture::new(name, inputs, assertions, errors)
+    Ok(RefuteFixture::new(name, inputs, assertions, errors))
+}
+
+/// A refute fixture for an imported token. Tokens that do not decode
+/// have every assertion omitted.
+pub fn imported(
+    name: String,
+    token: String,
+    proofs: Vec<String>,
+    errors: Vec<RefuteError>,
+) -> RefuteFixture {
+    let assertions = match Ucan::from_str(&token) {
+        Ok(ucan) => ucan_to_assertions(ucan),
+        Err(_) => empty_assertions(),
+    };
+
+    let mut fixture = RefuteFixture::new(name, Inputs { token, proofs }, assertions, errors);
+    fixture.category = String::from("imported");
+    fixture.assertions.decode_token(&fixture.inputs.token);
+
+    fixture
 }
 
 // TIME BOUNDS
 
-async fn expired(identities: Rc<Identities<Ed25519KeyMaterial>>) -> RefuteFixture {
+async fn expired(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<RefuteFixture> {
     make_fixture(
         String::from("UCAN has expired"),
         &identities.alice_key,
         identities.bob_did.clone(),
         UcanOptions {
             expiration: Some(1),
-            ..Default::default()
+            ..UcanOptions::for_version(version)
         },
-        HashMap::new(),
-        vec!["expired".into()],
+        vec![],
+        vec![RefuteError::Expired],
     )
     .await
 }
 
-async fn not_ready(identities: Rc<Identities<Ed25519KeyMaterial>>) -> RefuteFixture {
+async fn not_ready(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<RefuteFixture> {
     make_fixture(
         String::from("UCAN is not ready to be used"),
         &identities.alice_key,
         identities.bob_did.clone(),
         UcanOptions {
             not_before: Some(9246211200),
-            ..Default::default()
+            ..UcanOptions::for_version(version)
+        },
+        vec![],
+        vec![RefuteError::NotReady],
+    )
+    .await
+}
+
+// Both bounds fail against the current time, but the pair is also empty
+// on its own, whatever the time
+async fn not_before_after_expiration(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<RefuteFixture> {
+    make_fixture(
+        String::from("UCAN nbf is after exp"),
+        &identities.alice_key,
+        identities.bob_did.clone(),
+        UcanOptions {
+            not_before: Some(9246211200),
+            expiration: Some(1),
+            ..UcanOptions::for_version(version)
         },
-        HashMap::new(),
-        vec!["notReady".into()],
+        vec![],
+        vec![RefuteError::TimeBoundsViolation],
     )
     .await
 }
 
-async fn expires_after_proofs(identities: Rc<Identities<Ed25519KeyMaterial>>) -> RefuteFixture {
+async fn expires_after_proofs(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<RefuteFixture> {
     let (proof_ucan_cid, proof_token) = make_proof(
         &identities.alice_key,
         identities.bob_did.clone(),
         UcanOptions {
             expiration: Some(9246211200),
-            ..Default::default()
+            ..UcanOptions::for_version(version)
         },
     )
-    .await;
+    .await?;
 
     make_fixture(
         String::from("UCAN expires after proofs"),
@@ -180,25 +367,67 @@ async fn expires_after_proofs(identities: Rc<Identities<Ed25519KeyMaterial>>) ->
         identities.bob_did.clone(),
         UcanOptions {
             expiration: Some(14069142000),
-            proofs: vec![proof_ucan_cid.clone()],
-            ..Default::default()
+            proofs: vec![proof_ucan_cid],
+            ..UcanOptions::for_version(version)
+        },
+        vec![proof_token],
+        vec![RefuteError::TimeBoundsViolation],
+    )
+    .await
+}
+
+// Only the proof is expired. A token cannot be current and still fit inside
+// an expired proof's time bounds, so an implementation may report either
+// the expired proof or the time bounds violation.
+async fn proof_expired(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<RefuteFixture> {
+    let send_email_as_alice: Capability = EMAIL_SEMANTICS
+        .parse("mailto:alice@email.com", "email/send", None)
+        .unwrap()
+        .into();
+
+    let (proof_ucan_cid, proof_token) = make_proof(
+        &identities.alice_key,
+        identities.bob_did.clone(),
+        UcanOptions {
+            capabilities: vec![send_email_as_alice.clone()],
+            expiration: Some(1),
+            ..UcanOptions::for_version(version)
         },
-        HashMap::from([(proof_ucan_cid, proof_token)]),
-        vec!["timeBoundsViolation".into()],
+    )
+    .await?;
+
+    make_fixture(
+        String::from("UCAN proof is expired"),
+        &identities.bob_key,
+        identities.mallory_did.clone(),
+        UcanOptions {
+            capabilities: vec![send_email_as_alice],
+            expiration: Some(9246211200),
+            proofs: vec![proof_ucan_cid],
+            ..UcanOptions::for_version(version)
+        },
+        vec![proof_token],
+        vec![RefuteError::Expired, RefuteError::TimeBoundsViolation],
     )
     .await
 }
 
-async fn ready_before_proofs(identities: Rc<Identities<Ed25519KeyMaterial>>) -> RefuteFixture {
+async fn ready_before_proofs(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<RefuteFixture> {
     let (proof_ucan_cid, proof_token) = make_proof(
         &identities.alice_key,
         identities.bob_did.clone(),
         UcanOptions {
             not_before: Some(2),
-            ..Default::default()
+            ..UcanOptions::for_version(version)
         },
     )
-    .await;
+    .await?;
 
     make_fixture(
         String::from("UCAN ready before proofs"),
@@ -206,31 +435,299 @@ async fn ready_before_proofs(identities: Rc<Identities<Ed25519KeyMaterial>>) ->
         identities.bob_did.clone(),
         UcanOptions {
             not_before: Some(1),
-            proofs: vec![proof_ucan_cid.clone()],
-            ..Default::default()
+            proofs: vec![proof_ucan_cid],
+            ..UcanOptions::for_version(version)
+        },
+        vec![proof_token],
+        vec![RefuteError::TimeBoundsViolation],
+    )
+    .await
+}
+
+async fn capability_ok_time_escalates(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<RefuteFixture> {
+    let send_email_as_alice: Capability = EMAIL_SEMANTICS
+        .parse("mailto:alice@email.com", "email/send", None)
+        .unwrap()
+        .into();
+
+    let (proof_ucan_cid, proof_token) = make_proof(
+        &identities.alice_key,
+        identities.bob_did.clone(),
+        UcanOptions {
+            capabilities: vec![send_email_as_alice.clone()],
+            not_before: Some(2),
+            expiration: Some(9246211200),
+            ..UcanOptions::for_version(version)
+        },
+    )
+    .await?;
+
+    make_fixture(
+        String::from("UCAN claims a delegated capability outside the proof time window"),
+        &identities.bob_key,
+        identities.mallory_did.clone(),
+        UcanOptions {
+            capabilities: vec![send_email_as_alice],
+            not_before: Some(1),
+            expiration: Some(14069142000),
+            proofs: vec![proof_ucan_cid],
+            ..UcanOptions::for_version(version)
         },
-        HashMap::from([(proof_ucan_cid, proof_token)]),
-        vec!["timeBoundsViolation".into()],
+        vec![proof_token],
+        vec![RefuteError::TimeBoundsViolation],
     )
     .await
 }
 
+// Expired by more than the clock skew at the fixture time
+async fn expired_outside_clock_skew(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<RefuteFixture> {
+    Ok(make_fixture(
+        String::from("UCAN expired outside the clock skew tolerance"),
+        &identities.alice_key,
+        identities.bob_did.clone(),
+        UcanOptions {
+            expiration: Some(CLOCK_SKEW_TIME - CLOCK_SKEW_SECONDS * 3 / 2),
+            ..UcanOptions::for_version(version)
+        },
+        vec![],
+        vec![RefuteError::Expired],
+    )
+    .await?
+    .with_clock_skew())
+}
+
+// Becomes ready more than the clock skew after the fixture time
+async fn not_ready_outside_clock_skew(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<RefuteFixture> {
+    Ok(make_fixture(
+        String::from("UCAN becomes ready outside the clock skew tolerance"),
+        &identities.alice_key,
+        identities.bob_did.clone(),
+        UcanOptions {
+            not_before: Some(CLOCK_SKEW_TIME + CLOCK_SKEW_SECONDS * 3 / 2),
+            ..UcanOptions::for_version(version)
+        },
+        vec![],
+        vec![RefuteError::NotReady],
+    )
+    .await?
+    .with_clock_skew())
+}
+
 // ENCODING
 
+async fn trailing_signature_data(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<RefuteFixture> {
+    let mut fixture = make_fixture(
+        String::from("UCAN signature has trailing data"),
+        &identities.alice_key,
+        identities.bob_did.clone(),
+        UcanOptions {
+            ..UcanOptions::for_version(version)
+        },
+        vec![],
+        vec![RefuteError::Malformed],
+    )
+    .await?;
+
+    // Lenient base64 decoders stop at the garbage and accept the signature
+    *fixture.inputs.token_mut() = format!("{}!GARBAGE!", fixture.inputs.token);
+
+    Ok(fixture)
+}
+
+// `*` is outside every base64 alphabet
+async fn header_not_base64(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<RefuteFixture> {
+    let mut fixture = make_fixture(
+        String::from("UCAN header segment contains a non-base64 character"),
+        &identities.alice_key,
+        identities.bob_did.clone(),
+        UcanOptions {
+            ..UcanOptions::for_version(version)
+        },
+        vec![],
+        vec![RefuteError::MalformedEncoding],
+    )
+    .await?;
+
+    *fixture.inputs.token_mut() = corrupt_part(
+        fixture.inputs.token.as_str(),
+        "header",
+        "*",
+        identities.alice_key.clone(),
+    )?;
+
+    Ok(fixture)
+}
+
+// Segments are unpadded, and padding can never appear mid-segment
+async fn payload_inner_padding(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<RefuteFixture> {
+    let mut fixture = make_fixture(
+        String::from("UCAN payload segment contains padding"),
+        &identities.alice_key,
+        identities.bob_did.clone(),
+        UcanOptions {
+            ..UcanOptions::for_version(version)
+        },
+        vec![],
+        vec![RefuteError::MalformedEncoding],
+    )
+    .await?;
+
+    *fixture.inputs.token_mut() = corrupt_part(
+        fixture.inputs.token.as_str(),
+        "payload",
+        "==",
+        identities.alice_key.clone(),
+    )?;
+
+    Ok(fixture)
+}
+
+// `+` and `/` are standard base64, which lenient decoders accept as
+// base64url
+async fn signature_not_url_safe(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<RefuteFixture> {
+    let mut fixture = make_fixture(
+        String::from("UCAN signature segment uses the standard base64 alphabet"),
+        &identities.alice_key,
+        identities.bob_did.clone(),
+        UcanOptions {
+            ..UcanOptions::for_version(version)
+        },
+        vec![],
+        vec![RefuteError::MalformedEncoding],
+    )
+    .await?;
+
+    *fixture.inputs.token_mut() = corrupt_part(
+        fixture.inputs.token.as_str(),
+        "signature",
+        "+/",
+        identities.alice_key.clone(),
+    )?;
+
+    Ok(fixture)
+}
+
+// Parsers that split on `.` and index the parts must bounds check
+async fn missing_signature_segment(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<RefuteFixture> {
+    let mut fixture = make_fixture(
+        String::from("UCAN has two segments"),
+        &identities.alice_key,
+        identities.bob_did.clone(),
+        UcanOptions {
+            ..UcanOptions::for_version(version)
+        },
+        vec![],
+        vec![RefuteError::MalformedEncoding],
+    )
+    .await?;
+
+    *fixture.inputs.token_mut() = drop_signature(fixture.inputs.token.as_str())?;
+
+    Ok(fixture)
+}
+
+async fn extra_segment(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<RefuteFixture> {
+    let mut fixture = make_fixture(
+        String::from("UCAN has four segments"),
+        &identities.alice_key,
+        identities.bob_did.clone(),
+        UcanOptions {
+            ..UcanOptions::for_version(version)
+        },
+        vec![],
+        vec![RefuteError::MalformedEncoding],
+    )
+    .await?;
+
+    let signature = fixture.inputs.token.rsplit('.').next().unwrap().to_string();
+    *fixture.inputs.token_mut() = append_segment(fixture.inputs.token.as_str(), &signature);
+
+    Ok(fixture)
+}
+
+// Recursive parsers can overflow the stack on deep nesting, so validators
+// must reject the payload at a depth limit rather than crash
+async fn deeply_nested_facts(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<RefuteFixture> {
+    let options = UcanOptions {
+        facts: BTreeMap::from([(String::from("nested"), json!([]))]),
+        ..UcanOptions::for_version(version)
+    };
+    let payload = encode_payload(&identities.alice_key, identities.bob_did.clone(), &options)
+        .await?
+        .replacen(
+            r#""nested":[]"#,
+            &format!(
+                r#""nested":{}{}"#,
+                "[".repeat(NESTED_FACTS_DEPTH),
+                "]".repeat(NESTED_FACTS_DEPTH)
+            ),
+            1,
+        );
+
+    let mut fixture = make_fixture(
+        String::from("UCAN payload fct field is nested too deeply"),
+        &identities.alice_key,
+        identities.bob_did.clone(),
+        options,
+        vec![],
+        vec![RefuteError::Malformed],
+    )
+    .await?;
+
+    let header = raw_part(fixture.inputs.token.as_str(), "header")?;
+    *fixture.assertions.payload.fct_mut() = None;
+    *fixture.inputs.token_mut() = build_raw_token(&header, &payload, identities.alice_key.clone())?;
+
+    Ok(fixture.with_conformance(Conformance::Should))
+}
+
 // MISSING FIELDS
 
-async fn missing_algorithm(identities: Rc<Identities<Ed25519KeyMaterial>>) -> RefuteFixture {
+async fn missing_algorithm(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<RefuteFixture> {
     let mut fixture = make_fixture(
         String::from("UCAN header is missing alg field"),
         &identities.alice_key,
         identities.bob_did.clone(),
         UcanOptions {
-            ..Default::default()
+            ..UcanOptions::for_version(version)
         },
-        HashMap::new(),
-        vec!["missingField".into()],
+        vec![],
+        vec![RefuteError::MissingField],
     )
-    .await;
+    .await?;
 
     // *fixture.assertions.header.alg_mut() = None;
     *fixture.assertions.header.alg_mut() = None;
@@ -239,23 +736,26 @@ async fn missing_algorithm(identities: Rc<Identities<Ed25519KeyMaterial>>) -> Re
         "header",
         "alg",
         identities.alice_key.clone(),
-    );
+    )?;
 
-    fixture
+    Ok(fixture)
 }
 
-async fn missing_type(identities: Rc<Identities<Ed25519KeyMaterial>>) -> RefuteFixture {
+async fn missing_type(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<RefuteFixture> {
     let mut fixture = make_fixture(
         String::from("UCAN header is missing typ field"),
         &identities.alice_key,
         identities.bob_did.clone(),
         UcanOptions {
-            ..Default::default()
+            ..UcanOptions::for_version(version)
         },
-        HashMap::new(),
-        vec!["missingField".into()],
+        vec![],
+        vec![RefuteError::MissingField],
     )
-    .await;
+    .await?;
 
     *fixture.assertions.header.typ_mut() = None;
     *fixture.inputs.token_mut() = remove_field(
@@ -263,23 +763,26 @@ async fn missing_type(identities: Rc<Identities<Ed25519KeyMaterial>>) -> RefuteF
         "header",
         "typ",
         identities.alice_key.clone(),
-    );
+    )?;
 
-    fixture
+    Ok(fixture)
 }
 
-async fn missing_version(identities: Rc<Identities<Ed25519KeyMaterial>>) -> RefuteFixture {
+async fn missing_version(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<RefuteFixture> {
     let mut fixture = make_fixture(
         String::from("UCAN payload is missing ucv field"),
         &identities.alice_key,
         identities.bob_did.clone(),
         UcanOptions {
-            ..Default::default()
+            ..UcanOptions::for_version(version)
         },
-        HashMap::new(),
-        vec!["missingField".into()],
+        vec![],
+        vec![RefuteError::MissingField],
     )
-    .await;
+    .await?;
 
     *fixture.assertions.payload.ucv_mut() = None;
     *fixture.inputs.token_mut() = remove_field(
@@ -287,23 +790,26 @@ async fn missing_version(identities: Rc<Identities<Ed25519KeyMaterial>>) -> Refu
         "payload",
         "ucv",
         identities.alice_key.clone(),
-    );
+    )?;
 
-    fixture
+    Ok(fixture)
 }
 
-async fn missing_issuer(identities: Rc<Identities<Ed25519KeyMaterial>>) -> RefuteFixture {
+async fn missing_issuer(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<RefuteFixture> {
     let mut fixture = make_fixture(
         String::from("UCAN payload is missing iss field"),
         &identities.alice_key,
         identities.bob_did.clone(),
         UcanOptions {
-            ..Default::default()
+            ..UcanOptions::for_version(version)
         },
-        HashMap::new(),
-        vec!["missingField".into()],
+        vec![],
+        vec![RefuteError::MissingField],
     )
-    .await;
+    .await?;
 
     *fixture.assertions.payload.iss_mut() = None;
     *fixture.inputs.token_mut() = remove_field(
@@ -311,23 +817,26 @@ async fn missing_issuer(identities: Rc<Identities<Ed25519KeyMaterial>>) -> Refut
         "payload",
         "iss",
         identities.alice_key.clone(),
-    );
+    )?;
 
-    fixture
+    Ok(fixture)
 }
 
-async fn missing_audience(identities: Rc<Identities<Ed25519KeyMaterial>>) -> RefuteFixture {
+async fn missing_audience(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<RefuteFixture> {
     let mut fixture = make_fixture(
         String::from("UCAN payload is missing aud field"),
         &identities.alice_key,
         identities.bob_did.clone(),
         UcanOptions {
-            ..Default::default()
+            ..UcanOptions::for_version(version)
         },
-        HashMap::new(),
-        vec!["missingField".into()],
+        vec![],
+        vec![RefuteError::MissingField],
     )
-    .await;
+    .await?;
 
     *fixture.assertions.payload.aud_mut() = None;
     *fixture.inputs.token_mut() = remove_field(
@@ -335,23 +844,26 @@ async fn missing_audience(identities: Rc<Identities<Ed25519KeyMaterial>>) -> Ref
         "payload",
         "aud",
         identities.alice_key.clone(),
-    );
+    )?;
 
-    fixture
+    Ok(fixture)
 }
 
-async fn missing_expiration(identities: Rc<Identities<Ed25519KeyMaterial>>) -> RefuteFixture {
+async fn missing_expiration(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<RefuteFixture> {
     let mut fixture = make_fixture(
         String::from("UCAN payload is missing exp field"),
         &identities.alice_key,
         identities.bob_did.clone(),
         UcanOptions {
-            ..Default::default()
+            ..UcanOptions::for_version(version)
         },
-        HashMap::new(),
-        vec!["missingField".into()],
+        vec![],
+        vec![RefuteError::MissingField],
     )
-    .await;
+    .await?;
 
     // Some(86) is a special marker value to remove exp from the assertions
     *fixture.assertions.payload.exp_mut() = Some(86);
@@ -360,23 +872,26 @@ async fn missing_expiration(identities: Rc<Identities<Ed25519KeyMaterial>>) -> R
         "payload",
         "exp",
         identities.alice_key.clone(),
-    );
+    )?;
 
-    fixture
+    Ok(fixture)
 }
 
-async fn missing_capabilities(identities: Rc<Identities<Ed25519KeyMaterial>>) -> RefuteFixture {
+async fn missing_capabilities(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<RefuteFixture> {
     let mut fixture = make_fixture(
         String::from("UCAN payload is missing cap field"),
         &identities.alice_key,
         identities.bob_did.clone(),
         UcanOptions {
-            ..Default::default()
+            ..UcanOptions::for_version(version)
         },
-        HashMap::new(),
-        vec!["missingField".into()],
+        vec![],
+        vec![RefuteError::MissingField],
     )
-    .await;
+    .await?;
 
     *fixture.assertions.payload.cap_mut() = None;
     *fixture.inputs.token_mut() = remove_field(
@@ -384,25 +899,28 @@ async fn missing_capabilities(identities: Rc<Identities<Ed25519KeyMaterial>>) ->
         "payload",
         "cap",
         identities.alice_key.clone(),
-    );
+    )?;
 
-    fixture
+    Ok(fixture)
 }
 
 // INVALID FIELDS
 
-async fn invalid_algorithm(identities: Rc<Identities<Ed25519KeyMaterial>>) -> RefuteFixture {
+async fn invalid_algorithm(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<RefuteFixture> {
     let mut fixture = make_fixture(
         String::from("UCAN header alg field is not a string"),
         &identities.alice_key,
         identities.bob_did.clone(),
         UcanOptions {
-            ..Default::default()
+            ..UcanOptions::for_version(version)
         },
-        HashMap::new(),
-        vec!["incorrectType".into()],
+        vec![],
+        vec![RefuteError::IncorrectType],
     )
-    .await;
+    .await?;
 
     *fixture.assertions.header.alg_mut() = None;
     *fixture.inputs.token_mut() = mutate_field(
@@ -411,23 +929,55 @@ async fn invalid_algorithm(identities: Rc<Identities<Ed25519KeyMaterial>>) -> Re
         "alg",
         json!(1),
         identities.alice_key.clone(),
-    );
+    )?;
 
-    fixture
+    Ok(fixture)
+}
+
+async fn alg_signature_mismatch(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<RefuteFixture> {
+    let mut fixture = make_fixture(
+        String::from("UCAN header alg field does not match the signature scheme"),
+        &identities.alice_key,
+        identities.bob_did.clone(),
+        UcanOptions {
+            ..UcanOptions::for_version(version)
+        },
+        vec![],
+        vec![RefuteError::InvalidSignature],
+    )
+    .await?;
+
+    // Still signed with Ed25519 after the header claims RS256
+    *fixture.assertions.header.alg_mut() = None;
+    *fixture.inputs.token_mut() = mutate_field(
+        fixture.inputs.token.as_str(),
+        "header",
+        "alg",
+        json!("RS256"),
+        identities.alice_key.clone(),
+    )?;
+
+    Ok(fixture)
 }
 
-async fn invalid_type(identities: Rc<Identities<Ed25519KeyMaterial>>) -> RefuteFixture {
+async fn invalid_type(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<RefuteFixture> {
     let mut fixture = make_fixture(
         String::from("UCAN header typ field is not a string"),
         &identities.alice_key,
         identities.bob_did.clone(),
         UcanOptions {
-            ..Default::default()
+            ..UcanOptions::for_version(version)
         },
-        HashMap::new(),
-        vec!["incorrectType".into()],
+        vec![],
+        vec![RefuteError::IncorrectType],
     )
-    .await;
+    .await?;
 
     *fixture.assertions.header.typ_mut() = None;
     *fixture.inputs.token_mut() = mutate_field(
@@ -436,23 +986,26 @@ async fn invalid_type(identities: Rc<Identities<Ed25519KeyMaterial>>) -> RefuteF
         "typ",
         json!(1),
         identities.alice_key.clone(),
-    );
+    )?;
 
-    fixture
+    Ok(fixture)
 }
 
-async fn invalid_type_not_jwt(identities: Rc<Identities<Ed25519KeyMaterial>>) -> RefuteFixture {
+async fn invalid_type_not_jwt(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<RefuteFixture> {
     let mut fixture = make_fixture(
         String::from("UCAN type is not JWT"),
         &identities.alice_key,
         identities.bob_did.clone(),
         UcanOptions {
-            ..Default::default()
+            ..UcanOptions::for_version(version)
         },
-        HashMap::new(),
-        vec!["incorrectType".into()],
+        vec![],
+        vec![RefuteError::IncorrectType],
     )
-    .await;
+    .await?;
 
     *fixture.assertions.header.typ_mut() = None;
     *fixture.inputs.token_mut() = mutate_field(
@@ -461,23 +1014,26 @@ async fn invalid_type_not_jwt(identities: Rc<Identities<Ed25519KeyMaterial>>) ->
         "typ",
         json!("NOT_JWT"),
         identities.alice_key.clone(),
-    );
+    )?;
 
-    fixture
+    Ok(fixture)
 }
 
-async fn invalid_version(identities: Rc<Identities<Ed25519KeyMaterial>>) -> RefuteFixture {
+async fn invalid_version(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<RefuteFixture> {
     let mut fixture = make_fixture(
         String::from("UCAN payload ucv field is not a string"),
         &identities.alice_key,
         identities.bob_did.clone(),
         UcanOptions {
-            ..Default::default()
+            ..UcanOptions::for_version(version)
         },
-        HashMap::new(),
-        vec!["incorrectType".into()],
+        vec![],
+        vec![RefuteError::IncorrectType],
     )
-    .await;
+    .await?;
 
     *fixture.assertions.payload.ucv_mut() = None;
     *fixture.inputs.token_mut() = mutate_field(
@@ -486,25 +1042,26 @@ async fn invalid_version(identities: Rc<Identities<Ed25519KeyMaterial>>) -> Refu
         "ucv",
         json!(1),
         identities.alice_key.clone(),
-    );
+    )?;
 
-    fixture
+    Ok(fixture)
 }
 
 async fn invalid_version_not_semantic(
     identities: Rc<Identities<Ed25519KeyMaterial>>,
-) -> RefuteFixture {
+    version: &str,
+) -> Result<RefuteFixture> {
     let mut fixture = make_fixture(
         String::from("UCAN payload ucv field is not semantically versioned"),
         &identities.alice_key,
         identities.bob_did.clone(),
         UcanOptions {
-            ..Default::default()
+            ..UcanOptions::for_version(version)
         },
-        HashMap::new(),
-        vec!["incorrectType".into()],
+        vec![],
+        vec![RefuteError::IncorrectType],
     )
-    .await;
+    .await?;
 
     *fixture.assertions.payload.ucv_mut() = None;
     *fixture.inputs.token_mut() = mutate_field(
@@ -513,23 +1070,26 @@ async fn invalid_version_not_semantic(
         "ucv",
         json!("0.10"),
         identities.alice_key.clone(),
-    );
+    )?;
 
-    fixture
+    Ok(fixture)
 }
 
-async fn invalid_issuer(identities: Rc<Identities<Ed25519KeyMaterial>>) -> RefuteFixture {
+async fn invalid_issuer(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<RefuteFixture> {
     let mut fixture = make_fixture(
         String::from("UCAN payload iss field is not a DID"),
         &identities.alice_key,
         identities.bob_did.clone(),
         UcanOptions {
-            ..Default::default()
+            ..UcanOptions::for_version(version)
         },
-        HashMap::new(),
-        vec!["incorrectType".into()],
+        vec![],
+        vec![RefuteError::IncorrectType],
     )
-    .await;
+    .await?;
 
     *fixture.assertions.payload.iss_mut() = None;
     *fixture.inputs.token_mut() = mutate_field(
@@ -538,49 +1098,85 @@ async fn invalid_issuer(identities: Rc<Identities<Ed25519KeyMaterial>>) -> Refut
         "iss",
         json!("z6Mkk89bC3JrVqKie71YEcc5M1SMVxuCgNx6zLZ8SYJsxALi"),
         identities.alice_key.clone(),
-    );
+    )?;
 
-    fixture
+    Ok(fixture)
 }
 
-async fn invalid_audience(identities: Rc<Identities<Ed25519KeyMaterial>>) -> RefuteFixture {
+// Unlike a string that is not a DID, did:web is well formed, and only
+// unsupported by implementations that resolve did:key alone
+async fn unsupported_issuer_did_method(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<RefuteFixture> {
     let mut fixture = make_fixture(
-        String::from("UCAN payload aud field is not a DID"),
+        String::from("UCAN payload iss field is a DID with an unsupported method"),
         &identities.alice_key,
         identities.bob_did.clone(),
         UcanOptions {
-            ..Default::default()
+            ..UcanOptions::for_version(version)
         },
-        HashMap::new(),
-        vec!["incorrectType".into()],
+        vec![],
+        vec![RefuteError::UnsupportedDid],
     )
-    .await;
+    .await?;
 
-    *fixture.assertions.payload.aud_mut() = None;
+    *fixture.assertions.payload.iss_mut() = None;
     *fixture.inputs.token_mut() = mutate_field(
         fixture.inputs.token.as_str(),
         "payload",
-        "aud",
-        json!("z6MkffDZCkCTWreg8868fG1FGFogcJj5X6PY93pPcWDn9bob"),
+        "iss",
+        json!("did:web:example.com"),
         identities.alice_key.clone(),
-    );
+    )?;
 
-    fixture
+    Ok(fixture)
 }
 
-async fn invalid_not_before(identities: Rc<Identities<Ed25519KeyMaterial>>) -> RefuteFixture {
+async fn invalid_audience(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<RefuteFixture> {
     let mut fixture = make_fixture(
-        String::from("UCAN payload nbf field is not a number"),
+        String::from("UCAN payload aud field is not a DID"),
+        &identities.alice_key,
+        identities.bob_did.clone(),
+        UcanOptions {
+            ..UcanOptions::for_version(version)
+        },
+        vec![],
+        vec![RefuteError::IncorrectType],
+    )
+    .await?;
+
+    *fixture.assertions.payload.aud_mut() = None;
+    *fixture.inputs.token_mut() = mutate_field(
+        fixture.inputs.token.as_str(),
+        "payload",
+        "aud",
+        json!("z6MkffDZCkCTWreg8868fG1FGFogcJj5X6PY93pPcWDn9bob"),
+        identities.alice_key.clone(),
+    )?;
+
+    Ok(fixture)
+}
+
+async fn invalid_not_before(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<RefuteFixture> {
+    let mut fixture = make_fixture(
+        String::from("UCAN payload nbf field is not a number"),
         &identities.alice_key,
         identities.bob_did.clone(),
         UcanOptions {
             not_before: Some(1),
-            ..Default::default()
+            ..UcanOptions::for_version(version)
         },
-        HashMap::new(),
-        vec!["incorrectType".into()],
+        vec![],
+        vec![RefuteError::IncorrectType],
     )
-    .await;
+    .await?;
 
     *fixture.assertions.payload.nbf_mut() = None;
     *fixture.inputs.token_mut() = mutate_field(
@@ -589,24 +1185,27 @@ async fn invalid_not_before(identities: Rc<Identities<Ed25519KeyMaterial>>) -> R
         "nbf",
         json!("1"),
         identities.alice_key.clone(),
-    );
+    )?;
 
-    fixture
+    Ok(fixture)
 }
 
-async fn invalid_expiration(identities: Rc<Identities<Ed25519KeyMaterial>>) -> RefuteFixture {
+async fn invalid_expiration(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<RefuteFixture> {
     let mut fixture = make_fixture(
         String::from("UCAN payload exp field is not a number"),
         &identities.alice_key,
         identities.bob_did.clone(),
         UcanOptions {
             expiration: Some(9246211200),
-            ..Default::default()
+            ..UcanOptions::for_version(version)
         },
-        HashMap::new(),
-        vec!["incorrectType".into()],
+        vec![],
+        vec![RefuteError::IncorrectType],
     )
-    .await;
+    .await?;
 
     // Some(86) is a special marker value to remove exp from the assertions
     *fixture.assertions.payload.exp_mut() = Some(86);
@@ -616,24 +1215,27 @@ async fn invalid_expiration(identities: Rc<Identities<Ed25519KeyMaterial>>) -> R
         "exp",
         json!("9246211200"),
         identities.alice_key.clone(),
-    );
+    )?;
 
-    fixture
+    Ok(fixture)
 }
 
-async fn invalid_nonce(identities: Rc<Identities<Ed25519KeyMaterial>>) -> RefuteFixture {
+async fn invalid_nonce(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<RefuteFixture> {
     let mut fixture = make_fixture(
         String::from("UCAN payload nnc field is not a string"),
         &identities.alice_key,
         identities.bob_did.clone(),
         UcanOptions {
-            add_nonce: true,
-            ..Default::default()
+            nonce: Some(String::from("b9ZxQe2LmT5wHs0u")),
+            ..UcanOptions::for_version(version)
         },
-        HashMap::new(),
-        vec!["incorrectType".into()],
+        vec![],
+        vec![RefuteError::IncorrectType],
     )
-    .await;
+    .await?;
 
     *fixture.assertions.payload.nnc_mut() = None;
     *fixture.inputs.token_mut() = mutate_field(
@@ -642,24 +1244,27 @@ async fn invalid_nonce(identities: Rc<Identities<Ed25519KeyMaterial>>) -> Refute
         "nnc",
         json!(1),
         identities.alice_key.clone(),
-    );
+    )?;
 
-    fixture
+    Ok(fixture)
 }
 
-async fn invalid_facts(identities: Rc<Identities<Ed25519KeyMaterial>>) -> RefuteFixture {
+async fn invalid_facts(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<RefuteFixture> {
     let mut fixture = make_fixture(
         String::from("UCAN payload fct field is not a JSON object"),
         &identities.alice_key,
         identities.bob_did.clone(),
         UcanOptions {
             facts: BTreeMap::from([(String::from("challenge"), json!("abcdef"))]),
-            ..Default::default()
+            ..UcanOptions::for_version(version)
         },
-        HashMap::new(),
-        vec!["incorrectType".into()],
+        vec![],
+        vec![RefuteError::IncorrectType],
     )
-    .await;
+    .await?;
 
     *fixture.assertions.payload.fct_mut() = None;
     *fixture.inputs.token_mut() = mutate_field(
@@ -668,12 +1273,15 @@ async fn invalid_facts(identities: Rc<Identities<Ed25519KeyMaterial>>) -> Refute
         "fct",
         json!(null),
         identities.alice_key.clone(),
-    );
+    )?;
 
-    fixture
+    Ok(fixture)
 }
 
-async fn invalid_capabilities(identities: Rc<Identities<Ed25519KeyMaterial>>) -> RefuteFixture {
+async fn invalid_capabilities(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<RefuteFixture> {
     let send_email_as_alice: Capability = EMAIL_SEMANTICS
         .parse("mailto:alice@email.com", "email/send", None)
         .unwrap()
@@ -685,12 +1293,12 @@ async fn invalid_capabilities(identities: Rc<Identities<Ed25519KeyMaterial>>) ->
         identities.bob_did.clone(),
         UcanOptions {
             capabilities: vec![send_email_as_alice],
-            ..Default::default()
+            ..UcanOptions::for_version(version)
         },
-        HashMap::new(),
-        vec!["incorrectType".into()],
+        vec![],
+        vec![RefuteError::IncorrectType],
     )
-    .await;
+    .await?;
 
     *fixture.assertions.payload.cap_mut() = None;
     *fixture.inputs.token_mut() = mutate_field(
@@ -699,14 +1307,49 @@ async fn invalid_capabilities(identities: Rc<Identities<Ed25519KeyMaterial>>) ->
         "cap",
         json!(null),
         identities.alice_key.clone(),
-    );
+    )?;
 
-    fixture
+    Ok(fixture)
+}
+
+async fn capabilities_is_array(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<RefuteFixture> {
+    let send_email_as_alice: Capability = EMAIL_SEMANTICS
+        .parse("mailto:alice@email.com", "email/send", None)
+        .unwrap()
+        .into();
+
+    let mut fixture = make_fixture(
+        String::from("UCAN payload cap field is an array"),
+        &identities.alice_key,
+        identities.bob_did.clone(),
+        UcanOptions {
+            capabilities: vec![send_email_as_alice],
+            ..UcanOptions::for_version(version)
+        },
+        vec![],
+        vec![RefuteError::IncorrectType],
+    )
+    .await?;
+
+    *fixture.assertions.payload.cap_mut() = None;
+    *fixture.inputs.token_mut() = mutate_field(
+        fixture.inputs.token.as_str(),
+        "payload",
+        "cap",
+        json!([]),
+        identities.alice_key.clone(),
+    )?;
+
+    Ok(fixture)
 }
 
 async fn invalid_capabilities_ability(
     identities: Rc<Identities<Ed25519KeyMaterial>>,
-) -> RefuteFixture {
+    version: &str,
+) -> Result<RefuteFixture> {
     let send_email_as_alice: Capability = EMAIL_SEMANTICS
         .parse("mailto:alice@email.com", "email/send", None)
         .unwrap()
@@ -718,12 +1361,12 @@ async fn invalid_capabilities_ability(
         identities.bob_did.clone(),
         UcanOptions {
             capabilities: vec![send_email_as_alice],
-            ..Default::default()
+            ..UcanOptions::for_version(version)
         },
-        HashMap::new(),
-        vec!["incorrectType".into()],
+        vec![],
+        vec![RefuteError::IncorrectType],
     )
-    .await;
+    .await?;
 
     *fixture.assertions.payload.cap_mut() = None;
     *fixture.inputs.token_mut() = mutate_field(
@@ -732,14 +1375,15 @@ async fn invalid_capabilities_ability(
         "cap",
         json!({ "mailto:alice@email.com": null }),
         identities.alice_key.clone(),
-    );
+    )?;
 
-    fixture
+    Ok(fixture)
 }
 
 async fn invalid_capabilities_caveats(
     identities: Rc<Identities<Ed25519KeyMaterial>>,
-) -> RefuteFixture {
+    version: &str,
+) -> Result<RefuteFixture> {
     let send_email_as_alice: Capability = EMAIL_SEMANTICS
         .parse("mailto:alice@email.com", "email/send", None)
         .unwrap()
@@ -751,28 +1395,29 @@ async fn invalid_capabilities_caveats(
         identities.bob_did.clone(),
         UcanOptions {
             capabilities: vec![send_email_as_alice],
-            ..Default::default()
+            ..UcanOptions::for_version(version)
         },
-        HashMap::new(),
-        vec!["incorrectType".into()],
+        vec![],
+        vec![RefuteError::IncorrectType],
     )
-    .await;
+    .await?;
 
     *fixture.assertions.payload.cap_mut() = None;
-    *fixture.inputs.token_mut() = mutate_field(
+    *fixture.inputs.token_mut() = mutate_path(
         fixture.inputs.token.as_str(),
         "payload",
-        "cap",
-        json!( { "mailto:alice@email.com": { "email/send": null }}),
+        &["cap", "mailto:alice@email.com", "email/send"],
+        json!(null),
         identities.alice_key.clone(),
-    );
+    )?;
 
-    fixture
+    Ok(fixture)
 }
 
 async fn invalid_capabilities_caveats_empty(
     identities: Rc<Identities<Ed25519KeyMaterial>>,
-) -> RefuteFixture {
+    version: &str,
+) -> Result<RefuteFixture> {
     let send_email_as_alice: Capability = EMAIL_SEMANTICS
         .parse("mailto:alice@email.com", "email/send", None)
         .unwrap()
@@ -784,38 +1429,329 @@ async fn invalid_capabilities_caveats_empty(
         identities.bob_did.clone(),
         UcanOptions {
             capabilities: vec![send_email_as_alice],
-            ..Default::default()
+            ..UcanOptions::for_version(version)
+        },
+        vec![],
+        vec![RefuteError::IncorrectType],
+    )
+    .await?;
+
+    *fixture.assertions.payload.cap_mut() = None;
+    *fixture.inputs.token_mut() = mutate_path(
+        fixture.inputs.token.as_str(),
+        "payload",
+        &["cap", "mailto:alice@email.com", "email/send"],
+        json!([]),
+        identities.alice_key.clone(),
+    )?;
+
+    Ok(fixture)
+}
+
+/// The empty caveat `{}` grants any use, so listing it beside a
+/// restriction is contradictory. Rather than normalizing the array to
+/// `[{}]`, validators must reject it as malformed.
+async fn mixed_any_and_restricted_caveat(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<RefuteFixture> {
+    let send_email_as_alice: Capability = EMAIL_SEMANTICS
+        .parse("mailto:alice@email.com", "email/send", None)
+        .unwrap()
+        .into();
+
+    let mut fixture = make_fixture(
+        String::from("UCAN payload cap field caveat mixes the any caveat with a restriction"),
+        &identities.alice_key,
+        identities.bob_did.clone(),
+        UcanOptions {
+            capabilities: vec![send_email_as_alice],
+            ..UcanOptions::for_version(version)
         },
-        HashMap::new(),
-        vec!["incorrectType".into()],
+        vec![],
+        vec![RefuteError::MalformedCapability],
     )
-    .await;
+    .await?;
+
+    *fixture.assertions.payload.cap_mut() = None;
+    *fixture.inputs.token_mut() = mutate_path(
+        fixture.inputs.token.as_str(),
+        "payload",
+        &["cap", "mailto:alice@email.com", "email/send"],
+        json!([{}, { "templates": ["x"] }]),
+        identities.alice_key.clone(),
+    )?;
+
+    Ok(fixture.with_conformance(Conformance::Should))
+}
+
+// Lenient JSON parsers keep either the first or the last duplicate, so the
+// caveat must be rejected rather than read as one of them
+async fn duplicate_caveat_key(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<RefuteFixture> {
+    let caveat = json!({"templates": ["a"]});
+    let send_email_as_alice: Capability = EMAIL_SEMANTICS
+        .parse("mailto:alice@email.com", "email/send", Some(&caveat))
+        .unwrap()
+        .into();
+
+    let options = UcanOptions {
+        capabilities: vec![send_email_as_alice],
+        ..UcanOptions::for_version(version)
+    };
+    let payload = encode_payload(&identities.alice_key, identities.bob_did.clone(), &options)
+        .await?
+        .replacen(
+            r#"{"templates":["a"]}"#,
+            r#"{"templates":["a"],"templates":["b"]}"#,
+            1,
+        );
+
+    let mut fixture = make_fixture(
+        String::from("UCAN payload cap field caveat has a duplicate key"),
+        &identities.alice_key,
+        identities.bob_did.clone(),
+        options,
+        vec![],
+        vec![RefuteError::Malformed],
+    )
+    .await?;
+
+    let header = raw_part(fixture.inputs.token.as_str(), "header")?;
+    *fixture.assertions.payload.cap_mut() = None;
+    *fixture.inputs.token_mut() = build_raw_token(&header, &payload, identities.alice_key.clone())?;
+
+    Ok(fixture)
+}
+
+async fn ability_double_slash(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<RefuteFixture> {
+    let send_email_as_alice: Capability = EMAIL_SEMANTICS
+        .parse("mailto:alice@email.com", "email/send", None)
+        .unwrap()
+        .into();
+
+    let mut fixture = make_fixture(
+        String::from("UCAN payload cap field ability has an empty segment"),
+        &identities.alice_key,
+        identities.bob_did.clone(),
+        UcanOptions {
+            capabilities: vec![send_email_as_alice],
+            ..UcanOptions::for_version(version)
+        },
+        vec![],
+        vec![RefuteError::MalformedCapability],
+    )
+    .await?;
 
     *fixture.assertions.payload.cap_mut() = None;
     *fixture.inputs.token_mut() = mutate_field(
         fixture.inputs.token.as_str(),
         "payload",
         "cap",
-        json!( { "mailto:alice@email.com": { "email/send": []}}),
+        json!({ "mailto:alice@email.com": { "email//send": [{}] }}),
         identities.alice_key.clone(),
-    );
+    )?;
 
-    fixture
+    Ok(fixture.with_conformance(Conformance::Should))
 }
 
-async fn invalid_proofs(identities: Rc<Identities<Ed25519KeyMaterial>>) -> RefuteFixture {
+// Only the top ability `*` may omit the namespace
+async fn ability_not_namespaced(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<RefuteFixture> {
+    let send_email_as_alice: Capability = EMAIL_SEMANTICS
+        .parse("mailto:alice@email.com", "email/send", None)
+        .unwrap()
+        .into();
+
+    let mut fixture = make_fixture(
+        String::from("UCAN payload cap field ability has no namespace"),
+        &identities.alice_key,
+        identities.bob_did.clone(),
+        UcanOptions {
+            capabilities: vec![send_email_as_alice],
+            ..UcanOptions::for_version(version)
+        },
+        vec![],
+        vec![RefuteError::MalformedCapability],
+    )
+    .await?;
+
+    *fixture.assertions.payload.cap_mut() = None;
+    *fixture.inputs.token_mut() = mutate_field(
+        fixture.inputs.token.as_str(),
+        "payload",
+        "cap",
+        json!({ "mailto:alice@email.com": { "send": [{}] }}),
+        identities.alice_key.clone(),
+    )?;
+
+    Ok(fixture.with_conformance(Conformance::Should))
+}
+
+async fn ability_trailing_slash(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<RefuteFixture> {
+    let send_email_as_alice: Capability = EMAIL_SEMANTICS
+        .parse("mailto:alice@email.com", "email/send", None)
+        .unwrap()
+        .into();
+
+    let mut fixture = make_fixture(
+        String::from("UCAN payload cap field ability ends with an empty segment"),
+        &identities.alice_key,
+        identities.bob_did.clone(),
+        UcanOptions {
+            capabilities: vec![send_email_as_alice],
+            ..UcanOptions::for_version(version)
+        },
+        vec![],
+        vec![RefuteError::MalformedCapability],
+    )
+    .await?;
+
+    *fixture.assertions.payload.cap_mut() = None;
+    *fixture.inputs.token_mut() = mutate_field(
+        fixture.inputs.token.as_str(),
+        "payload",
+        "cap",
+        json!({ "mailto:alice@email.com": { "email/": [{}] }}),
+        identities.alice_key.clone(),
+    )?;
+
+    Ok(fixture.with_conformance(Conformance::Should))
+}
+
+// "123" is a valid JSON key but neither a URI nor a namespaced ability, so
+// implementations that accept any string key must still reject it
+async fn numeric_string_capability(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<RefuteFixture> {
+    let send_email_as_alice: Capability = EMAIL_SEMANTICS
+        .parse("mailto:alice@email.com", "email/send", None)
+        .unwrap()
+        .into();
+
+    let mut fixture = make_fixture(
+        String::from("UCAN payload cap field resource and ability are numeric strings"),
+        &identities.alice_key,
+        identities.bob_did.clone(),
+        UcanOptions {
+            capabilities: vec![send_email_as_alice],
+            ..UcanOptions::for_version(version)
+        },
+        vec![],
+        vec![RefuteError::MalformedCapability],
+    )
+    .await?;
+
+    *fixture.assertions.payload.cap_mut() = None;
+    *fixture.inputs.token_mut() = mutate_field(
+        fixture.inputs.token.as_str(),
+        "payload",
+        "cap",
+        json!({ "123": { "456": [{}] }}),
+        identities.alice_key.clone(),
+    )?;
+
+    Ok(fixture.with_conformance(Conformance::Should))
+}
+
+// Well formed JSON, but the resource has no scheme, so it is not a URI
+async fn resource_not_uri(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<RefuteFixture> {
+    let send_email_as_alice: Capability = EMAIL_SEMANTICS
+        .parse("mailto:alice@email.com", "email/send", None)
+        .unwrap()
+        .into();
+
+    let mut fixture = make_fixture(
+        String::from("UCAN payload cap field resource is not a URI"),
+        &identities.alice_key,
+        identities.bob_did.clone(),
+        UcanOptions {
+            capabilities: vec![send_email_as_alice],
+            ..UcanOptions::for_version(version)
+        },
+        vec![],
+        vec![RefuteError::MalformedCapability],
+    )
+    .await?;
+
+    *fixture.assertions.payload.cap_mut() = None;
+    *fixture.inputs.token_mut() = mutate_field(
+        fixture.inputs.token.as_str(),
+        "payload",
+        "cap",
+        json!({ "not-a-uri": { "email/send": [{}] }}),
+        identities.alice_key.clone(),
+    )?;
+
+    Ok(fixture)
+}
+
+// Parsers disagree on duplicate keys, some keeping the first and some the
+// last, so implementations would disagree on what was delegated
+async fn duplicate_capability_resource(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<RefuteFixture> {
+    let send_email_as_alice: Capability = EMAIL_SEMANTICS
+        .parse("mailto:alice@email.com", "email/send", None)
+        .unwrap()
+        .into();
+
+    let mut fixture = make_fixture(
+        String::from("UCAN payload cap field has a duplicate resource"),
+        &identities.alice_key,
+        identities.bob_did.clone(),
+        UcanOptions {
+            capabilities: vec![send_email_as_alice],
+            ..UcanOptions::for_version(version)
+        },
+        vec![],
+        vec![RefuteError::MalformedCapability],
+    )
+    .await?;
+
+    *fixture.assertions.payload.cap_mut() = None;
+    *fixture.inputs.token_mut() = duplicate_payload_key(
+        fixture.inputs.token.as_str(),
+        "cap",
+        "mailto:alice@email.com",
+        json!({ "email/send": [{ "templates": ["newsletter"] }] }),
+        identities.alice_key.clone(),
+    )?;
+
+    Ok(fixture)
+}
+
+async fn invalid_proofs(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<RefuteFixture> {
     let mut fixture = make_fixture(
         String::from("UCAN payload prf field is not an array"),
         &identities.alice_key,
         identities.bob_did.clone(),
         UcanOptions {
             proofs: vec![String::from("placeholder")],
-            ..Default::default()
+            ..UcanOptions::for_version(version)
         },
-        HashMap::new(),
-        vec!["incorrectType".into()],
+        vec![],
+        vec![RefuteError::IncorrectType],
     )
-    .await;
+    .await?;
 
     *fixture.assertions.payload.prf_mut() = None;
     *fixture.inputs.token_mut() = mutate_field(
@@ -824,24 +1760,27 @@ async fn invalid_proofs(identities: Rc<Identities<Ed25519KeyMaterial>>) -> Refut
         "prf",
         json!({}),
         identities.alice_key.clone(),
-    );
+    )?;
 
-    fixture
+    Ok(fixture)
 }
 
-async fn invalid_proof_cids(identities: Rc<Identities<Ed25519KeyMaterial>>) -> RefuteFixture {
+async fn invalid_proof_cids(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<RefuteFixture> {
     let mut fixture = make_fixture(
         String::from("UCAN payload prf field is not an array of CIDs"),
         &identities.alice_key,
         identities.bob_did.clone(),
         UcanOptions {
             proofs: vec![String::from("placeholder")],
-            ..Default::default()
+            ..UcanOptions::for_version(version)
         },
-        HashMap::new(),
-        vec!["incorrectProofs".into()],
+        vec![],
+        vec![RefuteError::IncorrectProofs],
     )
-    .await;
+    .await?;
 
     *fixture.assertions.payload.prf_mut() = None;
     *fixture.inputs.token_mut() = mutate_field(
@@ -850,37 +1789,94 @@ async fn invalid_proof_cids(identities: Rc<Identities<Ed25519KeyMaterial>>) -> R
         "prf",
         json!(["we", "prove", "nothing"]),
         identities.alice_key.clone(),
-    );
+    )?;
 
-    fixture
+    Ok(fixture)
+}
+
+// SIGNATURE
+
+async fn iss_key_does_not_verify(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<RefuteFixture> {
+    let mut fixture = make_fixture(
+        String::from("UCAN signature does not verify with the iss key"),
+        &identities.alice_key,
+        identities.mallory_did.clone(),
+        UcanOptions {
+            ..UcanOptions::for_version(version)
+        },
+        vec![],
+        vec![RefuteError::InvalidSignature],
+    )
+    .await?;
+
+    // Labeled as issued by bob, but still signed by alice
+    *fixture.assertions.payload.iss_mut() = None;
+    *fixture.inputs.token_mut() = mutate_field(
+        fixture.inputs.token.as_str(),
+        "payload",
+        "iss",
+        json!(identities.bob_did),
+        identities.alice_key.clone(),
+    )?;
+
+    Ok(fixture)
+}
+
+// A well-formed signature by mallory over a payload naming alice as iss
+async fn signed_by_other_key(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<RefuteFixture> {
+    let mut fixture = make_fixture(
+        String::from("UCAN signature is made by a key other than the iss key"),
+        &identities.alice_key,
+        identities.bob_did.clone(),
+        UcanOptions {
+            ..UcanOptions::for_version(version)
+        },
+        vec![],
+        vec![RefuteError::InvalidSignature],
+    )
+    .await?;
+
+    *fixture.inputs.token_mut() = resign_with(
+        fixture.inputs.token.as_str(),
+        identities.mallory_key.clone(),
+    )?;
+
+    Ok(fixture)
 }
 
 // DELEGATION
 
 async fn issuer_does_not_match_proof_audience(
     identities: Rc<Identities<Ed25519KeyMaterial>>,
-) -> RefuteFixture {
+    version: &str,
+) -> Result<RefuteFixture> {
     let (proof_ucan_cid, proof_token) = make_proof(
         &identities.alice_key,
         identities.bob_did.clone(),
         UcanOptions {
-            ..Default::default()
+            ..UcanOptions::for_version(version)
         },
     )
-    .await;
+    .await?;
 
     let mut fixture = make_fixture(
         String::from("UCAN issuer does not match proof audience"),
         &identities.bob_key,
         identities.mallory_did.clone(),
         UcanOptions {
-            proofs: vec![proof_ucan_cid.clone()],
-            ..Default::default()
+            proofs: vec![proof_ucan_cid],
+            ..UcanOptions::for_version(version)
         },
-        HashMap::from([(proof_ucan_cid, proof_token)]),
-        vec!["invalidDelegation".into()],
+        vec![proof_token],
+        vec![RefuteError::InvalidDelegation],
     )
-    .await;
+    .await?;
 
     *fixture.assertions.payload.iss_mut() = None;
     *fixture.inputs.token_mut() = mutate_field(
@@ -889,14 +1885,17 @@ async fn issuer_does_not_match_proof_audience(
         "iss",
         json!("did:key:z6MktafZTREjJkvV5mfJxcLpNBoVPwDLhTuMg9ng7dY4zMAL"),
         identities.alice_key.clone(),
-    );
+    )?;
 
-    fixture
+    Ok(fixture)
 }
 
-async fn claimed_capability_not_delegated(
+// One prf CID matches a provided proof. The other names a proof that was
+// never sent, which validators must not skip over.
+async fn dangling_proof_cid(
     identities: Rc<Identities<Ed25519KeyMaterial>>,
-) -> RefuteFixture {
+    version: &str,
+) -> Result<RefuteFixture> {
     let send_email_as_alice: Capability = EMAIL_SEMANTICS
         .parse("mailto:alice@email.com", "email/send", None)
         .unwrap()
@@ -906,36 +1905,84 @@ async fn claimed_capability_not_delegated(
         &identities.alice_key,
         identities.bob_did.clone(),
         UcanOptions {
-            ..Default::default()
+            capabilities: vec![send_email_as_alice.clone()],
+            ..UcanOptions::for_version(version)
+        },
+    )
+    .await?;
+    let (unsent_proof_ucan_cid, _) = make_proof(
+        &identities.mallory_key,
+        identities.bob_did.clone(),
+        UcanOptions {
+            ..UcanOptions::for_version(version)
         },
     )
-    .await;
+    .await?;
 
-    let mut fixture = make_fixture(
-        String::from("UCAN claims a capability that has not been delegated"),
+    make_fixture(
+        String::from("UCAN payload prf field references a proof that is not provided"),
         &identities.bob_key,
         identities.mallory_did.clone(),
         UcanOptions {
             capabilities: vec![send_email_as_alice],
-            proofs: vec![proof_ucan_cid.clone()],
-            ..Default::default()
+            proofs: vec![proof_ucan_cid, unsent_proof_ucan_cid],
+            ..UcanOptions::for_version(version)
         },
-        HashMap::from([(proof_ucan_cid, proof_token)]),
-        vec!["invalidDelegation".into()],
+        vec![proof_token],
+        vec![RefuteError::MissingProof],
     )
-    .await;
+    .await
+}
 
-    *fixture.assertions.payload.cap_mut() = None;
+async fn mixed_version_chain(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<RefuteFixture> {
+    let (_, proof_token) = make_proof(
+        &identities.alice_key,
+        identities.bob_did.clone(),
+        UcanOptions {
+            ..UcanOptions::for_version(version)
+        },
+    )
+    .await?;
 
-    fixture
+    let proof_token = mutate_field(
+        proof_token.as_str(),
+        "payload",
+        "ucv",
+        json!("0.9.0"),
+        identities.alice_key.clone(),
+    )?;
+    let proof_ucan_cid = token_to_cid(proof_token.as_str())?;
+
+    Ok(make_fixture(
+        String::from("UCAN version does not match proof version"),
+        &identities.bob_key,
+        identities.mallory_did.clone(),
+        UcanOptions {
+            proofs: vec![proof_ucan_cid],
+            ..UcanOptions::for_version(version)
+        },
+        vec![proof_token],
+        vec![RefuteError::VersionMismatch],
+    )
+    .await?
+    .with_conformance(Conformance::Should))
 }
 
-async fn caveats_escalate_with_new_caveat(
+// A proof for /a does not delegate /a/b. See `PathResource` for why
+// sub-paths must be delegated explicitly.
+async fn resource_subpath_claim(
     identities: Rc<Identities<Ed25519KeyMaterial>>,
-) -> RefuteFixture {
-    let caveat = json!({"templates": ["newsletter"]});
-    let send_email_as_alice: Capability = EMAIL_SEMANTICS
-        .parse("mailto:alice@email.com", "email/send", Some(&caveat))
+    version: &str,
+) -> Result<RefuteFixture> {
+    let read_a: Capability = PATH_SEMANTICS
+        .parse("https://example.com/a", "crud/read", None)
+        .unwrap()
+        .into();
+    let read_a_b: Capability = PATH_SEMANTICS
+        .parse("https://example.com/a/b", "crud/read", None)
         .unwrap()
         .into();
 
@@ -943,47 +1990,47 @@ async fn caveats_escalate_with_new_caveat(
         &identities.alice_key,
         identities.bob_did.clone(),
         UcanOptions {
-            capabilities: vec![send_email_as_alice],
-            ..Default::default()
+            capabilities: vec![read_a],
+            ..UcanOptions::for_version(version)
         },
     )
-    .await;
-
-    let escalated_caveat = json!({"templates": ["newsletter", "marketing"]});
-    let send_email_as_alice_escalated: Capability = EMAIL_SEMANTICS
-        .parse(
-            "mailto:alice@email.com",
-            "email/send",
-            Some(&escalated_caveat),
-        )
-        .unwrap()
-        .into();
+    .await?;
 
     let mut fixture = make_fixture(
-        String::from("UCAN escalates by adding a new caveat"),
+        String::from("UCAN claims a sub-path of the delegated resource"),
         &identities.bob_key,
         identities.mallory_did.clone(),
         UcanOptions {
-            capabilities: vec![send_email_as_alice_escalated],
-            proofs: vec![proof_ucan_cid.clone()],
-            ..Default::default()
+            capabilities: vec![read_a_b],
+            proofs: vec![proof_ucan_cid],
+            ..UcanOptions::for_version(version)
         },
-        HashMap::from([(proof_ucan_cid, proof_token)]),
-        vec!["invalidDelegation".into()],
+        vec![proof_token],
+        vec![RefuteError::InvalidDelegation],
     )
-    .await;
+    .await?;
 
     *fixture.assertions.payload.cap_mut() = None;
 
-    fixture
+    Ok(fixture)
 }
 
-async fn caveats_escalate_to_no_caveats(
+// A WNFS path contains only the paths beneath it, so claiming the parent
+// directory escalates
+async fn wnfs_path_escalates(
     identities: Rc<Identities<Ed25519KeyMaterial>>,
-) -> RefuteFixture {
-    let caveat = json!({"templates": ["newsletter"]});
-    let send_email_as_alice: Capability = EMAIL_SEMANTICS
-        .parse("mailto:alice@email.com", "email/send", Some(&caveat))
+    version: &str,
+) -> Result<RefuteFixture> {
+    let append_to_photos: Capability = WNFS_SEMANTICS
+        .parse(
+            "wnfs://alice.example.com/public/photos",
+            "wnfs/append",
+            None,
+        )
+        .unwrap()
+        .into();
+    let append_to_public: Capability = WNFS_SEMANTICS
+        .parse("wnfs://alice.example.com/public", "wnfs/append", None)
         .unwrap()
         .into();
 
@@ -991,42 +2038,46 @@ async fn caveats_escalate_to_no_caveats(
         &identities.alice_key,
         identities.bob_did.clone(),
         UcanOptions {
-            capabilities: vec![send_email_as_alice],
-            ..Default::default()
+            capabilities: vec![append_to_photos],
+            ..UcanOptions::for_version(version)
         },
     )
-    .await;
-
-    let send_email_as_alice_escalated: Capability = EMAIL_SEMANTICS
-        .parse("mailto:alice@email.com", "email/send", None)
-        .unwrap()
-        .into();
+    .await?;
 
     let mut fixture = make_fixture(
-        String::from("UCAN escalates to no caveats"),
+        String::from("UCAN escalates a WNFS capability above the delegated path"),
         &identities.bob_key,
         identities.mallory_did.clone(),
         UcanOptions {
-            capabilities: vec![send_email_as_alice_escalated],
-            proofs: vec![proof_ucan_cid.clone()],
-            ..Default::default()
+            capabilities: vec![append_to_public],
+            proofs: vec![proof_ucan_cid],
+            ..UcanOptions::for_version(version)
         },
-        HashMap::from([(proof_ucan_cid, proof_token)]),
-        vec!["invalidDelegation".into()],
+        vec![proof_token],
+        vec![RefuteError::InvalidDelegation],
     )
-    .await;
+    .await?;
 
     *fixture.assertions.payload.cap_mut() = None;
 
-    fixture
+    Ok(fixture)
 }
 
-async fn caveats_escalate_with_different_caveat(
+// HIERARCHY
+
+async fn escalates_ability(
     identities: Rc<Identities<Ed25519KeyMaterial>>,
-) -> RefuteFixture {
-    let caveat = json!({"templates": ["newsletter"]});
-    let send_email_as_alice: Capability = EMAIL_SEMANTICS
-        .parse("mailto:alice@email.com", "email/send", Some(&caveat))
+    semantics: &HierarchySemantics,
+    ability: &str,
+    escalated: &str,
+    version: &str,
+) -> Result<RefuteFixture> {
+    let granted: Capability = semantics
+        .parse(HIERARCHY_RESOURCE, ability, None)
+        .unwrap()
+        .into();
+    let claimed: Capability = semantics
+        .parse(HIERARCHY_RESOURCE, escalated, None)
         .unwrap()
         .into();
 
@@ -1034,37 +2085,27 @@ async fn caveats_escalate_with_different_caveat(
         &identities.alice_key,
         identities.bob_did.clone(),
         UcanOptions {
-            capabilities: vec![send_email_as_alice],
-            ..Default::default()
+            capabilities: vec![granted],
+            ..UcanOptions::for_version(version)
         },
     )
-    .await;
-
-    let escalated_caveat = json!({"templates": ["marketing"]});
-    let send_email_as_alice_escalated: Capability = EMAIL_SEMANTICS
-        .parse(
-            "mailto:alice@email.com",
-            "email/send",
-            Some(&escalated_caveat),
-        )
-        .unwrap()
-        .into();
+    .await?;
 
     let mut fixture = make_fixture(
-        String::from("UCAN escalates by adding a different caveat"),
+        format!("UCAN escalates to {escalated} from a proof granting {ability}"),
         &identities.bob_key,
         identities.mallory_did.clone(),
         UcanOptions {
-            capabilities: vec![send_email_as_alice_escalated],
-            proofs: vec![proof_ucan_cid.clone()],
-            ..Default::default()
+            capabilities: vec![claimed],
+            proofs: vec![proof_ucan_cid],
+            ..UcanOptions::for_version(version)
         },
-        HashMap::from([(proof_ucan_cid, proof_token)]),
-        vec!["invalidDelegation".into()],
+        vec![proof_token],
+        vec![RefuteError::InvalidDelegation],
     )
-    .await;
+    .await?;
 
     *fixture.assertions.payload.cap_mut() = None;
 
-    fixture
+    Ok(fixture)
 }
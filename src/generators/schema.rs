@@ -0,0 +1,51 @@
+use super::{
+    build::BuildFixture, disprove::DisproveFixture, prove::ProveFixture, refute::RefuteFixture,
+    to_cid::ToCIDFixture, verify::VerifyFixture,
+};
+use schemars::{
+    gen::{SchemaGenerator, SchemaSettings},
+    schema::{InstanceType, RootSchema, Schema, SchemaObject},
+    JsonSchema,
+};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+
+/// Stands in for `Capabilities` in schemas: caveats by ability by resource
+pub(crate) type CapabilitiesSchema = BTreeMap<String, BTreeMap<String, Vec<Map<String, Value>>>>;
+
+/// Stands in for `FactsMap` in schemas
+pub(crate) type FactsSchema = BTreeMap<String, Value>;
+
+/// A JSON Schema for each task file, keyed by the task file name
+pub fn schemas() -> BTreeMap<&'static str, RootSchema> {
+    BTreeMap::from([
+        ("verify.json", schema_for::<VerifyFixture>()),
+        ("refute.json", schema_for::<RefuteFixture>()),
+        ("prove.json", schema_for::<ProveFixture>()),
+        ("disprove.json", schema_for::<DisproveFixture>()),
+        ("build.json", schema_for::<BuildFixture>()),
+        ("cid.json", schema_for::<ToCIDFixture>()),
+    ])
+}
+
+/// A schema for a file holding an array of `T`. Unset optional fields are
+/// omitted rather than null, so options are not nullable unless a field
+/// opts in with `nullable_u64`.
+fn schema_for<T: JsonSchema>() -> RootSchema {
+    let settings = SchemaSettings::draft07().with(|settings| {
+        settings.option_nullable = false;
+        settings.option_add_null_type = false;
+    });
+
+    settings.into_generator().into_root_schema_for::<Vec<T>>()
+}
+
+/// An integer field serialized as null when unset, like `exp`
+pub(crate) fn nullable_u64(_: &mut SchemaGenerator) -> Schema {
+    SchemaObject {
+        instance_type: Some(vec![InstanceType::Integer, InstanceType::Null].into()),
+        format: Some(String::from("uint64")),
+        ..Default::default()
+    }
+    .into()
+}
@@ -1,18 +1,25 @@
-use super::UcanOptions;
+use super::{categorize, sign_with_options, Conformance, Fixture, UcanOptions};
 use crate::identities::Identities;
-use anyhow::Result;
-use cid::multihash::Code;
+use anyhow::{anyhow, bail, Context, Result};
+use cid::{
+    multihash::{Code, MultihashDigest},
+    Cid,
+};
+use futures::{future::try_join_all, FutureExt};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use std::{default::Default, rc::Rc};
-use ucan::{builder::Signable, Ucan};
+use std::rc::Rc;
+use ucan::Ucan;
 use ucan_key_support::ed25519::Ed25519KeyMaterial;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
 pub struct ToCIDFixture {
-    name: String,
-    task: String,
-    inputs: Inputs,
-    outputs: Outputs,
+    pub name: String,
+    pub task: String,
+    pub category: String,
+    pub conformance: Conformance,
+    pub inputs: Inputs,
+    pub outputs: Outputs,
 }
 
 impl ToCIDFixture {
@@ -20,101 +27,269 @@ impl ToCIDFixture {
         ToCIDFixture {
             name,
             task: "toCID".to_string(),
+            category: String::new(),
+            conformance: Conformance::Must,
             inputs,
             outputs,
         }
     }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn with_conformance(mut self, conformance: Conformance) -> Self {
+        self.conformance = conformance;
+        self
+    }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-struct Inputs {
-    token: String,
-    hasher: String,
+impl Fixture for ToCIDFixture {
+    fn category_mut(&mut self) -> &mut String {
+        &mut self.category
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct Inputs {
+    pub token: String,
+    pub hasher: String,
+    pub codec: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
 pub struct Outputs {
-    cid: String,
+    pub cid: String,
+    /// `dag-cbor` CIDs follow this generator's encoding of the token as a
+    /// DAG-CBOR text string, not one the UCAN spec defines
+    pub codec: String,
 }
 
-// GENERATE
+/// Multicodec codes for the fixture `codec` names
+const RAW_CODEC: u64 = 0x55;
+const DAG_CBOR_CODEC: u64 = 0x71;
 
-pub async fn generate() -> Result<Vec<ToCIDFixture>> {
-    let identities = Rc::new(Identities::new().await);
+// GENERATE
 
-    let fixtures: Vec<ToCIDFixture> = vec![
-        computes_cid_with_sha2_256_hasher(identities.clone()).await,
-        computes_cid_with_blake3_256_hasher(identities.clone()).await,
-    ];
+pub async fn generate(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<Vec<ToCIDFixture>> {
+    let fixtures: Vec<ToCIDFixture> = [
+        categorize(
+            "hasher",
+            try_join_all(vec![
+                computes_cid_with_sha2_256_hasher(identities.clone(), version).boxed_local(),
+                computes_cid_with_sha2_512_hasher(identities.clone(), version).boxed_local(),
+                computes_cid_with_sha3_256_hasher(identities.clone(), version).boxed_local(),
+                computes_cid_with_blake2b_256_hasher(identities.clone(), version).boxed_local(),
+                computes_cid_with_blake3_256_hasher(identities.clone(), version).boxed_local(),
+            ])
+            .await?,
+        ),
+        categorize(
+            "codec",
+            try_join_all(vec![computes_cid_with_dag_cbor_codec(
+                identities.clone(),
+                version,
+            )
+            .boxed_local()])
+            .await?,
+        ),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
 
     Ok(fixtures)
 }
 
+/// The multihash code for a fixture `hasher` name
+pub fn hasher_code(hasher: &str) -> Option<Code> {
+    match hasher {
+        "SHA2-256" => Some(Code::Sha2_256),
+        "SHA2-512" => Some(Code::Sha2_512),
+        "SHA3-256" => Some(Code::Sha3_256),
+        "BLAKE2b-256" => Some(Code::Blake2b256),
+        "BLAKE3-256" => Some(Code::Blake3_256),
+        _ => None,
+    }
+}
+
+/// The multicodec code for a fixture `codec` name
+pub fn codec_code(codec: &str) -> Option<u64> {
+    match codec {
+        "raw" => Some(RAW_CODEC),
+        "dag-cbor" => Some(DAG_CBOR_CODEC),
+        _ => None,
+    }
+}
+
+/// The CID of a token. Under `raw` the token bytes are hashed as they are,
+/// as `Ucan::to_cid` does. Under `dag-cbor` the token is hashed as a
+/// DAG-CBOR text string. The UCAN spec does not define a DAG-CBOR CID for a
+/// JWT token, so that encoding is this generator's own.
+pub fn token_cid(token: &str, hasher: Code, codec: u64) -> Result<Cid> {
+    let bytes = match codec {
+        RAW_CODEC => token.as_bytes().to_vec(),
+        DAG_CBOR_CODEC => dag_cbor_text(token),
+        _ => bail!("Unknown codec {:#x}", codec),
+    };
+
+    Ok(Cid::new_v1(codec, hasher.digest(&bytes)))
+}
+
+/// Encode a string as a DAG-CBOR text string, major type 3 with the
+/// shortest length header
+fn dag_cbor_text(text: &str) -> Vec<u8> {
+    let len = text.len() as u64;
+    let mut bytes = match len {
+        0..=23 => vec![0x60 | len as u8],
+        24..=0xff => vec![0x78, len as u8],
+        0x100..=0xffff => [&[0x79][..], &(len as u16).to_be_bytes()].concat(),
+        0x1_0000..=0xffff_ffff => [&[0x7a][..], &(len as u32).to_be_bytes()].concat(),
+        _ => [&[0x7b][..], &len.to_be_bytes()].concat(),
+    };
+    bytes.extend_from_slice(text.as_bytes());
+
+    bytes
+}
+
 async fn make_fixture(
     name: String,
     issuer: &Ed25519KeyMaterial,
     audience: String,
     hasher: String,
+    codec: String,
     options: UcanOptions,
-) -> ToCIDFixture {
-    let signable = Signable {
-        issuer: &issuer.clone(),
-        audience: audience.clone(),
-        capabilities: options.capabilities,
-        expiration: options.expiration,
-        not_before: options.not_before,
-        facts: options.facts,
-        proofs: options.proofs,
-        add_nonce: options.add_nonce,
-    };
+) -> Result<ToCIDFixture> {
+    let ucan = sign_with_options(issuer, audience.clone(), options)
+        .await
+        .with_context(|| format!("Could not sign fixture: {}", name))?;
+    let token = Ucan::encode(&ucan)?;
 
-    let ucan = signable.sign().await.unwrap();
-    let token = Ucan::encode(&ucan).unwrap();
+    // A fallback would silently hash with the wrong function or codec
+    let hasher_code =
+        hasher_code(&hasher).ok_or_else(|| anyhow!("Unknown hasher {} in: {}", hasher, name))?;
+    let codec_code =
+        codec_code(&codec).ok_or_else(|| anyhow!("Unknown codec {} in: {}", codec, name))?;
+    let cid = token_cid(&token, hasher_code, codec_code)?.to_string();
 
     let inputs = Inputs {
         token,
-        hasher: hasher.clone(),
-    };
-
-    let hasher_code = match hasher.as_str() {
-        "SHA2-256" => Code::Sha2_256,
-        "BLAKE3-256" => Code::Blake3_256,
-        _ => Code::Sha2_256,
+        hasher,
+        codec: codec.clone(),
     };
-    let cid = ucan.to_cid(hasher_code).unwrap().to_string();
-    let outputs = Outputs { cid };
+    let outputs = Outputs { cid, codec };
 
-    ToCIDFixture::new(name, inputs, outputs)
+    Ok(ToCIDFixture::new(name, inputs, outputs))
 }
 
 // TO CID
 
 async fn computes_cid_with_sha2_256_hasher(
     identities: Rc<Identities<Ed25519KeyMaterial>>,
-) -> ToCIDFixture {
+    version: &str,
+) -> Result<ToCIDFixture> {
     make_fixture(
         String::from("Compute CID for token using SHA2-256 hasher"),
         &identities.alice_key,
         identities.bob_did.clone(),
         String::from("SHA2-256"),
+        String::from("raw"),
         UcanOptions {
-            ..Default::default()
+            ..UcanOptions::for_version(version)
         },
     )
     .await
 }
 
+async fn computes_cid_with_sha2_512_hasher(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<ToCIDFixture> {
+    Ok(make_fixture(
+        String::from("Compute CID for token using SHA2-512 hasher"),
+        &identities.alice_key,
+        identities.bob_did.clone(),
+        String::from("SHA2-512"),
+        String::from("raw"),
+        UcanOptions {
+            ..UcanOptions::for_version(version)
+        },
+    )
+    .await?
+    .with_conformance(Conformance::May))
+}
+
+async fn computes_cid_with_sha3_256_hasher(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<ToCIDFixture> {
+    Ok(make_fixture(
+        String::from("Compute CID for token using SHA3-256 hasher"),
+        &identities.alice_key,
+        identities.bob_did.clone(),
+        String::from("SHA3-256"),
+        String::from("raw"),
+        UcanOptions {
+            ..UcanOptions::for_version(version)
+        },
+    )
+    .await?
+    .with_conformance(Conformance::May))
+}
+
+async fn computes_cid_with_blake2b_256_hasher(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<ToCIDFixture> {
+    Ok(make_fixture(
+        String::from("Compute CID for token using BLAKE2b-256 hasher"),
+        &identities.alice_key,
+        identities.bob_did.clone(),
+        String::from("BLAKE2b-256"),
+        String::from("raw"),
+        UcanOptions {
+            ..UcanOptions::for_version(version)
+        },
+    )
+    .await?
+    .with_conformance(Conformance::May))
+}
+
 async fn computes_cid_with_blake3_256_hasher(
     identities: Rc<Identities<Ed25519KeyMaterial>>,
-) -> ToCIDFixture {
-    make_fixture(
+    version: &str,
+) -> Result<ToCIDFixture> {
+    Ok(make_fixture(
         String::from("Compute CID for token using BLAKE3-256 hasher"),
         &identities.alice_key,
         identities.bob_did.clone(),
         String::from("BLAKE3-256"),
+        String::from("raw"),
         UcanOptions {
-            ..Default::default()
+            ..UcanOptions::for_version(version)
         },
     )
-    .await
+    .await?
+    .with_conformance(Conformance::May))
+}
+
+async fn computes_cid_with_dag_cbor_codec(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<ToCIDFixture> {
+    Ok(make_fixture(
+        String::from("Compute CID for token using DAG-CBOR codec"),
+        &identities.alice_key,
+        identities.bob_did.clone(),
+        String::from("SHA2-256"),
+        String::from("dag-cbor"),
+        UcanOptions {
+            ..UcanOptions::for_version(version)
+        },
+    )
+    .await?
+    .with_conformance(Conformance::May))
 }
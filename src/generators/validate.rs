@@ -0,0 +1,64 @@
+use super::{errors::RefuteError, AnyFixture};
+use anyhow::{Context, Result};
+use serde_json::Value;
+use std::str::FromStr;
+use ucan::Ucan;
+
+/// Decode every token the fixtures carry, so a generator or mutation bug
+/// fails the run instead of being written. A refute token is exempt when
+/// its fixture expects a structural error.
+pub fn validate(fixtures: &[AnyFixture]) -> Result<()> {
+    for fixture in fixtures {
+        let value = serde_json::to_value(fixture)?;
+        let exempt = matches!(fixture, AnyFixture::Refute(_)) && expects_structural_error(&value);
+
+        for (path, token) in tokens(&value) {
+            if exempt && path == "inputs.token" {
+                continue;
+            }
+
+            Ucan::from_str(token)
+                .with_context(|| format!("{}: {} does not decode", fixture.name(), path))?;
+        }
+    }
+
+    Ok(())
+}
+
+fn expects_structural_error(fixture: &Value) -> bool {
+    fixture["errors"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+        .filter_map(RefuteError::from_code)
+        .any(|error| error.is_structural())
+}
+
+/// Each token in a serialized fixture, with the path it was found at
+fn tokens(fixture: &Value) -> Vec<(String, &str)> {
+    let mut tokens = vec![];
+
+    for path in ["inputs.token", "outputs.token"] {
+        let (part, field) = path.split_once('.').unwrap();
+        if let Some(token) = fixture[part][field].as_str() {
+            tokens.push((String::from(path), token));
+        }
+    }
+
+    let proofs = fixture["inputs"]["proofs"].as_array().into_iter().flatten();
+    for (index, proof) in proofs.enumerate() {
+        if let Some(token) = proof.as_str() {
+            tokens.push((format!("inputs.proofs[{}]", index), token));
+        }
+    }
+
+    let encodings = fixture["encodings"].as_array().into_iter().flatten();
+    for (index, encoding) in encodings.enumerate() {
+        if let Some(token) = encoding["token"].as_str() {
+            tokens.push((format!("encodings[{}].token", index), token));
+        }
+    }
+
+    tokens
+}
@@ -1,27 +1,63 @@
-use super::{make_proof, UcanOptions};
+use super::{
+    categorize, make_proof, make_proof_chain,
+    mutate::{add_field, reverse_payload_keys},
+    sign_ucan, sign_with_options, token_to_cid, with_decoded, Conformance, Fixture, TokenFixture,
+    UcanOptions, CLOCK_SKEW_SECONDS, CLOCK_SKEW_TIME,
+};
+#[cfg(feature = "did-web")]
+use crate::identities::ALICE_DID_WEB;
 use crate::{
-    capabilities::EmailSemantics,
+    capabilities::{
+        AbilityHierarchy, EmailSemantics, HierarchySemantics, SegmentSemantics, WnfsSemantics,
+        HIERARCHY_RESOURCE,
+    },
+    crypto::{ed25519_key_from_base64, Secp256k1KeyMaterial},
     generators::assertions::{ucan_to_assertions, UcanAssertions},
-    identities::Identities,
+    identities::{Identities, KeyTypeIdentities, ALICE_SECONDARY_BASE64_KEY},
 };
-use anyhow::Result;
+use anyhow::{Context, Result};
+use cid::{multibase::Base, Cid};
+use futures::{future::try_join_all, FutureExt};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use std::collections::BTreeMap;
-use std::{collections::HashMap, default::Default, rc::Rc};
+use std::{rc::Rc, str::FromStr};
 use ucan::{
     builder::Signable,
-    capability::{Capability, CapabilitySemantics},
+    capability::{Capabilities, Capability, CapabilitySemantics},
+    crypto::KeyMaterial,
     Ucan,
 };
-use ucan_key_support::ed25519::Ed25519KeyMaterial;
+use ucan_key_support::{ed25519::Ed25519KeyMaterial, p256::P256KeyMaterial, rsa::RsaKeyMaterial};
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
 pub struct VerifyFixture {
-    name: String,
-    task: String,
-    inputs: Inputs,
-    assertions: UcanAssertions,
+    pub name: String,
+    pub task: String,
+    pub category: String,
+    pub conformance: Conformance,
+    pub inputs: Inputs,
+    pub assertions: UcanAssertions,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub clock_skew_seconds: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time: Option<u64>,
+    /// Encodings of the same claims as the input token, or of the same
+    /// claims under another nonce, each of which must verify, with the CID
+    /// each one hashes to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub encodings: Option<Vec<Encoding>>,
+    /// The did:key each DID of another method in the fixture resolves to,
+    /// standing in for resolving its DID document
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resolved_dids: Option<BTreeMap<String, String>>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct Encoding {
+    pub token: String,
+    pub cid: String,
 }
 
 impl VerifyFixture {
@@ -29,119 +65,298 @@ impl VerifyFixture {
         VerifyFixture {
             name,
             task: "verify".to_string(),
+            category: String::new(),
+            conformance: Conformance::Must,
             inputs,
             assertions,
+            clock_skew_seconds: None,
+            time: None,
+            encodings: None,
+            resolved_dids: None,
         }
     }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn with_conformance(mut self, conformance: Conformance) -> Self {
+        self.conformance = conformance;
+        self
+    }
+
+    /// Evaluate at `CLOCK_SKEW_TIME`, tolerating `CLOCK_SKEW_SECONDS`
+    fn with_clock_skew(mut self) -> Self {
+        self.clock_skew_seconds = Some(CLOCK_SKEW_SECONDS);
+        self.time = Some(CLOCK_SKEW_TIME);
+        self
+    }
+}
+
+impl Fixture for VerifyFixture {
+    fn category_mut(&mut self) -> &mut String {
+        &mut self.category
+    }
+}
+
+impl TokenFixture for VerifyFixture {
+    fn token_and_assertions(&mut self) -> (&str, &mut UcanAssertions) {
+        (self.inputs.token.as_str(), &mut self.assertions)
+    }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-struct Inputs {
-    token: String,
-    proofs: HashMap<String, String>,
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct Inputs {
+    pub token: String,
+    pub proofs: Vec<String>,
 }
 
 const EMAIL_SEMANTICS: EmailSemantics = EmailSemantics {};
+const SEGMENT_SEMANTICS: SegmentSemantics = SegmentSemantics {};
+const WNFS_SEMANTICS: WnfsSemantics = WnfsSemantics {};
 
 // GENERATE
 
-pub async fn generate() -> Result<Vec<VerifyFixture>> {
-    let identities = Rc::new(Identities::new().await);
-
-    let fixtures: Vec<VerifyFixture> = vec![
-        // Time bounds
-        not_expired(identities.clone()).await,
-        active(identities.clone()).await,
-        same_time_bounds(identities.clone()).await,
-        proof_expires_after(identities.clone()).await,
-        proof_active_before(identities.clone()).await,
-        // Capability
-        well_formed_capability(identities.clone()).await,
-        well_formed_capability_with_caveat(identities.clone()).await,
-        multiple_well_formed_capabilities(identities.clone()).await,
-        // Delegation
-        issuer_matches_proof_audience(identities.clone()).await,
-        has_delegated_capability(identities.clone()).await,
-        merges_delegated_capabilities(identities.clone()).await,
-        caveats_equal(identities.clone()).await,
-        caveats_attenuate(identities.clone()).await,
-        caveats_attenuate_from_no_caveats(identities.clone()).await,
-        // Facts
-        has_fact(identities.clone()).await,
-    ];
-
-    Ok(fixtures)
-}
-
-async fn make_fixture(
+pub async fn generate(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    key_types: &KeyTypeIdentities,
+    version: &str,
+) -> Result<Vec<VerifyFixture>> {
+    let rsa_identities = key_types.rsa.clone();
+    let secp256k1_identities = key_types.secp256k1.clone();
+    let p256_identities = key_types.p256.clone();
+
+    let fixtures: Vec<VerifyFixture> = [
+        categorize(
+            "time",
+            try_join_all(vec![
+                not_expired(identities.clone(), version).boxed_local(),
+                never_expires(identities.clone(), version).boxed_local(),
+                active(identities.clone(), version).boxed_local(),
+                same_time_bounds(identities.clone(), version).boxed_local(),
+                proof_expires_after(identities.clone(), version).boxed_local(),
+                proof_active_before(identities.clone(), version).boxed_local(),
+                expired_within_clock_skew(identities.clone(), version).boxed_local(),
+                not_ready_within_clock_skew(identities.clone(), version).boxed_local(),
+            ])
+            .await?,
+        ),
+        categorize(
+            "capability",
+            try_join_all(vec![
+                well_formed_capability(identities.clone(), version).boxed_local(),
+                well_formed_capability_with_caveat(identities.clone(), version).boxed_local(),
+                multiple_well_formed_capabilities(identities.clone(), version).boxed_local(),
+                merged_capabilities(identities.clone(), version).boxed_local(),
+                did_resource_with_fragment(identities.clone(), version).boxed_local(),
+                resource_with_port(identities.clone(), version).boxed_local(),
+            ])
+            .await?,
+        ),
+        categorize(
+            "delegation",
+            try_join_all(vec![
+                empty_proofs_present(identities.clone(), version).boxed_local(),
+                issuer_matches_proof_audience(identities.clone(), version).boxed_local(),
+                claims_subset_of_proof(identities.clone(), version).boxed_local(),
+                independent_resource_grants(identities.clone(), version).boxed_local(),
+                proof_cid_encoding_equivalence(identities.clone(), version).boxed_local(),
+                three_hop_delegation_chain(identities.clone(), version).boxed_local(),
+                four_party_delegation_chain(identities.clone(), version).boxed_local(),
+                self_redelegation_chain(identities.clone(), version).boxed_local(),
+                self_issued(identities.clone(), version).boxed_local(),
+                loopback_audience(identities.clone(), version).boxed_local(),
+                caveats_equal(identities.clone(), version).boxed_local(),
+                caveats_attenuate(identities.clone(), version).boxed_local(),
+                caveats_attenuate_from_no_caveats(identities.clone(), version).boxed_local(),
+                caveat_order_independent(identities.clone(), version).boxed_local(),
+                caveats_select_subset(identities.clone(), version).boxed_local(),
+                no_caveats_delegated_by_no_caveats(identities.clone(), version).boxed_local(),
+                capability_and_time_attenuate(identities.clone(), version).boxed_local(),
+                resource_scheme_case_insensitive(identities.clone(), version).boxed_local(),
+                resource_unicode_normalization(identities.clone(), version).boxed_local(),
+                wnfs_capability_delegated(identities.clone(), version).boxed_local(),
+                wnfs_capability_attenuates(identities.clone(), version).boxed_local(),
+                multi_segment_ability(identities.clone(), version).boxed_local(),
+            ])
+            .await?,
+        ),
+        categorize(
+            "facts",
+            try_join_all(vec![
+                has_fact(identities.clone(), version).boxed_local(),
+                facts_of_each_type(identities.clone(), version).boxed_local(),
+                caveat_references_fact(identities.clone(), version).boxed_local(),
+            ])
+            .await?,
+        ),
+        categorize(
+            "extremes",
+            try_join_all(vec![
+                minimal_ucan(identities.clone(), version).boxed_local(),
+                maximal_ucan(identities.clone(), version).boxed_local(),
+                full_payload_with_proof(identities.clone(), version).boxed_local(),
+                payload_key_order(identities.clone(), version).boxed_local(),
+                unknown_payload_field(identities.clone(), version).boxed_local(),
+                nonce_uniqueness_pair(identities.clone(), version).boxed_local(),
+            ])
+            .await?,
+        ),
+        categorize(
+            "keys",
+            try_join_all(vec![
+                rsa_signed(rsa_identities.clone(), version).boxed_local(),
+                rsa_has_delegated_capability(rsa_identities.clone(), version).boxed_local(),
+                secp256k1_signed(secp256k1_identities.clone(), version).boxed_local(),
+                p256_signed(p256_identities.clone(), version).boxed_local(),
+                mixed_key_type_chain(
+                    identities.clone(),
+                    rsa_identities.clone(),
+                    p256_identities.clone(),
+                    version,
+                )
+                .boxed_local(),
+            ])
+            .await?,
+        ),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+
+    #[cfg(feature = "did-web")]
+    let fixtures: Vec<VerifyFixture> = fixtures
+        .into_iter()
+        .chain(categorize(
+            "keys",
+            vec![did_web_issuer(identities.clone(), version).await?],
+        ))
+        .collect();
+
+    Ok(with_decoded(fixtures))
+}
+
+pub async fn generate_for_hierarchy(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    hierarchy: &AbilityHierarchy,
+    version: &str,
+) -> Result<Vec<VerifyFixture>> {
+    let semantics = hierarchy.semantics();
+
+    let mut fixtures: Vec<VerifyFixture> = vec![];
+    for (ability, implied) in hierarchy.implications() {
+        fixtures.push(
+            delegates_implied_ability(identities.clone(), &semantics, &ability, &implied, version)
+                .await?,
+        );
+    }
+
+    Ok(with_decoded(categorize("hierarchy", fixtures)))
+}
+
+async fn make_fixture<K: KeyMaterial>(
     name: String,
-    issuer: &Ed25519KeyMaterial,
+    issuer: &K,
     audience: String,
     options: UcanOptions,
-    proofs: HashMap<String, String>,
-) -> VerifyFixture {
-    let signable = Signable {
-        issuer: &issuer.clone(),
-        audience: audience.clone(),
-        capabilities: options.capabilities,
-        expiration: options.expiration,
-        not_before: options.not_before,
-        facts: options.facts,
-        proofs: options.proofs,
-        add_nonce: options.add_nonce,
-    };
-    let ucan = signable.sign().await.unwrap();
+    proofs: Vec<String>,
+) -> Result<VerifyFixture> {
+    let ucan = sign_with_options(issuer, audience.clone(), options)
+        .await
+        .with_context(|| format!("Could not sign fixture: {}", name))?;
+
+    fixture_from_ucan(name, ucan, proofs)
+}
 
+fn fixture_from_ucan(name: String, ucan: Ucan, proofs: Vec<String>) -> Result<VerifyFixture> {
     let inputs = Inputs {
-        token: Ucan::encode(&ucan).unwrap(),
+        token: Ucan::encode(&ucan)?,
         proofs,
     };
     let assertions = ucan_to_assertions(ucan);
 
-    VerifyFixture::new(name, inputs, assertions)
+    Ok(VerifyFixture::new(name, inputs, assertions))
+}
+
+/// A verify fixture for an imported token, which must decode
+pub fn imported(name: String, token: String, proofs: Vec<String>) -> Result<VerifyFixture> {
+    let ucan = Ucan::from_str(&token)?;
+
+    let mut fixture = fixture_from_ucan(name, ucan, proofs)?;
+    fixture.category = String::from("imported");
+    fixture.assertions.decode_token(&fixture.inputs.token);
+
+    Ok(fixture)
 }
 
 // TIME BOUNDS
 
-async fn not_expired(identities: Rc<Identities<Ed25519KeyMaterial>>) -> VerifyFixture {
+async fn not_expired(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<VerifyFixture> {
     make_fixture(
         String::from("UCAN has not expired"),
         &identities.alice_key,
         identities.bob_did.clone(),
         UcanOptions {
             expiration: Some(9246211200),
-            ..Default::default()
+            ..UcanOptions::for_version(version)
+        },
+        vec![],
+    )
+    .await
+}
+
+// A null exp means the token never expires
+async fn never_expires(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<VerifyFixture> {
+    make_fixture(
+        String::from("UCAN with a null exp never expires"),
+        &identities.alice_key,
+        identities.bob_did.clone(),
+        UcanOptions {
+            expiration: None,
+            ..UcanOptions::for_version(version)
         },
-        HashMap::new(),
+        vec![],
     )
     .await
 }
 
-async fn active(identities: Rc<Identities<Ed25519KeyMaterial>>) -> VerifyFixture {
+async fn active(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<VerifyFixture> {
     make_fixture(
         "UCAN is ready to be used".to_string(),
         &identities.alice_key,
         identities.bob_did.clone(),
         UcanOptions {
             not_before: Some(1),
-            ..Default::default()
+            ..UcanOptions::for_version(version)
         },
-        HashMap::new(),
+        vec![],
     )
     .await
 }
 
-async fn same_time_bounds(identities: Rc<Identities<Ed25519KeyMaterial>>) -> VerifyFixture {
+async fn same_time_bounds(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<VerifyFixture> {
     let (proof_ucan_cid, proof_token) = make_proof(
         &identities.alice_key,
         identities.bob_did.clone(),
         UcanOptions {
             not_before: Some(1),
             expiration: Some(9246211200),
-            ..Default::default()
+            ..UcanOptions::for_version(version)
         },
     )
-    .await;
+    .await?;
 
     make_fixture(
         "UCAN has same time bounds as proof".to_string(),
@@ -150,24 +365,27 @@ async fn same_time_bounds(identities: Rc<Identities<Ed25519KeyMaterial>>) -> Ver
         UcanOptions {
             not_before: Some(1),
             expiration: Some(9246211200),
-            proofs: vec![proof_ucan_cid.clone()],
-            ..Default::default()
+            proofs: vec![proof_ucan_cid],
+            ..UcanOptions::for_version(version)
         },
-        HashMap::from([(proof_ucan_cid, proof_token)]),
+        vec![proof_token],
     )
     .await
 }
 
-async fn proof_expires_after(identities: Rc<Identities<Ed25519KeyMaterial>>) -> VerifyFixture {
+async fn proof_expires_after(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<VerifyFixture> {
     let (proof_ucan_cid, proof_token) = make_proof(
         &identities.alice_key,
         identities.bob_did.clone(),
         UcanOptions {
             expiration: Some(14069142000),
-            ..Default::default()
+            ..UcanOptions::for_version(version)
         },
     )
-    .await;
+    .await?;
 
     make_fixture(
         "UCAN expires before proof".to_string(),
@@ -175,24 +393,27 @@ async fn proof_expires_after(identities: Rc<Identities<Ed25519KeyMaterial>>) ->
         identities.mallory_did.clone(),
         UcanOptions {
             expiration: Some(9246211200),
-            proofs: vec![proof_ucan_cid.clone()],
-            ..Default::default()
+            proofs: vec![proof_ucan_cid],
+            ..UcanOptions::for_version(version)
         },
-        HashMap::from([(proof_ucan_cid, proof_token)]),
+        vec![proof_token],
     )
     .await
 }
 
-async fn proof_active_before(identities: Rc<Identities<Ed25519KeyMaterial>>) -> VerifyFixture {
+async fn proof_active_before(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<VerifyFixture> {
     let (proof_ucan_cid, proof_token) = make_proof(
         &identities.alice_key,
         identities.bob_did.clone(),
         UcanOptions {
             not_before: Some(1),
-            ..Default::default()
+            ..UcanOptions::for_version(version)
         },
     )
-    .await;
+    .await?;
 
     make_fixture(
         "UCAN active after proof".to_string(),
@@ -200,17 +421,58 @@ async fn proof_active_before(identities: Rc<Identities<Ed25519KeyMaterial>>) ->
         identities.mallory_did.clone(),
         UcanOptions {
             not_before: Some(2),
-            proofs: vec![proof_ucan_cid.clone()],
-            ..Default::default()
+            proofs: vec![proof_ucan_cid],
+            ..UcanOptions::for_version(version)
         },
-        HashMap::from([(proof_ucan_cid, proof_token)]),
+        vec![proof_token],
     )
     .await
 }
 
+// Expired by less than the clock skew at the fixture time
+async fn expired_within_clock_skew(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<VerifyFixture> {
+    Ok(make_fixture(
+        String::from("UCAN expired within the clock skew tolerance"),
+        &identities.alice_key,
+        identities.bob_did.clone(),
+        UcanOptions {
+            expiration: Some(CLOCK_SKEW_TIME - CLOCK_SKEW_SECONDS / 2),
+            ..UcanOptions::for_version(version)
+        },
+        vec![],
+    )
+    .await?
+    .with_clock_skew())
+}
+
+// Becomes ready in less than the clock skew after the fixture time
+async fn not_ready_within_clock_skew(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<VerifyFixture> {
+    Ok(make_fixture(
+        String::from("UCAN becomes ready within the clock skew tolerance"),
+        &identities.alice_key,
+        identities.bob_did.clone(),
+        UcanOptions {
+            not_before: Some(CLOCK_SKEW_TIME + CLOCK_SKEW_SECONDS / 2),
+            ..UcanOptions::for_version(version)
+        },
+        vec![],
+    )
+    .await?
+    .with_clock_skew())
+}
+
 // CAPABILITY
 
-async fn well_formed_capability(identities: Rc<Identities<Ed25519KeyMaterial>>) -> VerifyFixture {
+async fn well_formed_capability(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<VerifyFixture> {
     let send_email_as_alice: Capability = EMAIL_SEMANTICS
         .parse("mailto:alice@email.com", "email/send", None)
         .unwrap()
@@ -222,16 +484,17 @@ async fn well_formed_capability(identities: Rc<Identities<Ed25519KeyMaterial>>)
         identities.bob_did.clone(),
         UcanOptions {
             capabilities: vec![send_email_as_alice],
-            ..Default::default()
+            ..UcanOptions::for_version(version)
         },
-        HashMap::new(),
+        vec![],
     )
     .await
 }
 
 async fn well_formed_capability_with_caveat(
     identities: Rc<Identities<Ed25519KeyMaterial>>,
-) -> VerifyFixture {
+    version: &str,
+) -> Result<VerifyFixture> {
     let caveat = json!({"templates": ["marketing"]});
     let send_email_as_alice: Capability = EMAIL_SEMANTICS
         .parse("mailto:alice@email.com", "email/send", Some(&caveat))
@@ -244,16 +507,17 @@ async fn well_formed_capability_with_caveat(
         identities.bob_did.clone(),
         UcanOptions {
             capabilities: vec![send_email_as_alice],
-            ..Default::default()
+            ..UcanOptions::for_version(version)
         },
-        HashMap::new(),
+        vec![],
     )
     .await
 }
 
 async fn multiple_well_formed_capabilities(
     identities: Rc<Identities<Ed25519KeyMaterial>>,
-) -> VerifyFixture {
+    version: &str,
+) -> Result<VerifyFixture> {
     let send_email_as_alice: Capability = EMAIL_SEMANTICS
         .parse("mailto:alice@email.com", "email/send", None)
         .unwrap()
@@ -271,124 +535,467 @@ async fn multiple_well_formed_capabilities(
         identities.bob_did.clone(),
         UcanOptions {
             capabilities: vec![send_email_as_alice, send_email_as_marketing],
-            ..Default::default()
+            ..UcanOptions::for_version(version)
+        },
+        vec![],
+    )
+    .await
+}
+
+async fn merged_capabilities(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<VerifyFixture> {
+    let newsletter_caveat = json!({"templates": ["newsletter"]});
+    let send_newsletter_as_alice: Capability = EMAIL_SEMANTICS
+        .parse(
+            "mailto:alice@email.com",
+            "email/send",
+            Some(&newsletter_caveat),
+        )
+        .unwrap()
+        .into();
+
+    let marketing_caveat = json!({"templates": ["marketing"]});
+    let send_marketing_as_alice: Capability = EMAIL_SEMANTICS
+        .parse(
+            "mailto:alice@email.com",
+            "email/send",
+            Some(&marketing_caveat),
+        )
+        .unwrap()
+        .into();
+
+    // Overlapping resource and ability merge into a single caveat array
+    let merged =
+        Capabilities::try_from(vec![send_newsletter_as_alice, send_marketing_as_alice]).unwrap();
+
+    make_fixture(
+        "UCAN has merged capabilities".to_string(),
+        &identities.alice_key,
+        identities.bob_did.clone(),
+        UcanOptions {
+            capabilities: merged.iter().collect(),
+            ..UcanOptions::for_version(version)
+        },
+        vec![],
+    )
+    .await
+}
+
+async fn did_resource_with_fragment(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<VerifyFixture> {
+    // The fragment is part of the resource and must round-trip unchanged
+    let sign_with_alice_key = Capability::new(
+        format!("{}#key-1", identities.alice_did),
+        String::from("crypto/sign"),
+        json!({}),
+    );
+
+    make_fixture(
+        "UCAN has a DID resource with a fragment".to_string(),
+        &identities.alice_key,
+        identities.bob_did.clone(),
+        UcanOptions {
+            capabilities: vec![sign_with_alice_key],
+            ..UcanOptions::for_version(version)
+        },
+        vec![],
+    )
+    .await
+}
+
+// The port is part of the resource, so a proof for
+// https://example.com:8443/api does not cover https://example.com/api
+async fn resource_with_port(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<VerifyFixture> {
+    let post_to_api = Capability::new(
+        String::from("https://example.com:8443/api"),
+        String::from("crud/create"),
+        json!({}),
+    );
+
+    let (proof_ucan_cid, proof_token) = make_proof(
+        &identities.alice_key,
+        identities.bob_did.clone(),
+        UcanOptions {
+            capabilities: vec![post_to_api.clone()],
+            ..UcanOptions::for_version(version)
+        },
+    )
+    .await?;
+
+    make_fixture(
+        "UCAN has an https resource with a port".to_string(),
+        &identities.bob_key,
+        identities.mallory_did.clone(),
+        UcanOptions {
+            capabilities: vec![post_to_api],
+            proofs: vec![proof_ucan_cid],
+            ..UcanOptions::for_version(version)
         },
-        HashMap::new(),
+        vec![proof_token],
     )
     .await
 }
 
 // DELEGATION
 
+async fn empty_proofs_present(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<VerifyFixture> {
+    let signable = Signable {
+        issuer: &identities.alice_key,
+        audience: identities.bob_did.clone(),
+        capabilities: vec![],
+        expiration: None,
+        not_before: None,
+        facts: BTreeMap::new(),
+        proofs: vec![],
+        add_nonce: false,
+    };
+
+    // Signable omits prf when there are no proofs, so set it directly
+    let mut payload = signable.ucan_payload().await?;
+    payload.ucv = String::from(version);
+    payload.prf = Some(vec![]);
+    let ucan = sign_ucan(&identities.alice_key, signable.ucan_header(), payload).await?;
+
+    fixture_from_ucan(String::from("UCAN has an empty prf field"), ucan, vec![])
+}
+
 async fn issuer_matches_proof_audience(
     identities: Rc<Identities<Ed25519KeyMaterial>>,
-) -> VerifyFixture {
+    version: &str,
+) -> Result<VerifyFixture> {
     let (proof_ucan_cid, proof_token) = make_proof(
         &identities.alice_key,
         identities.bob_did.clone(),
         UcanOptions {
-            ..Default::default()
+            ..UcanOptions::for_version(version)
         },
     )
-    .await;
+    .await?;
 
     make_fixture(
         String::from("UCAN issuer matches proof audience"),
         &identities.bob_key,
         identities.mallory_did.clone(),
         UcanOptions {
-            proofs: vec![proof_ucan_cid.clone()],
-            ..Default::default()
+            proofs: vec![proof_ucan_cid],
+            ..UcanOptions::for_version(version)
         },
-        HashMap::from([(proof_ucan_cid, proof_token)]),
+        vec![proof_token],
     )
     .await
 }
 
-async fn has_delegated_capability(identities: Rc<Identities<Ed25519KeyMaterial>>) -> VerifyFixture {
+async fn claims_subset_of_proof(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<VerifyFixture> {
     let send_email_as_alice: Capability = EMAIL_SEMANTICS
         .parse("mailto:alice@email.com", "email/send", None)
         .unwrap()
         .into();
+    let send_email_as_marketing: Capability = EMAIL_SEMANTICS
+        .parse("mailto:marketing@email.com", "email/send", None)
+        .unwrap()
+        .into();
+    let send_email_as_support: Capability = EMAIL_SEMANTICS
+        .parse("mailto:support@email.com", "email/send", None)
+        .unwrap()
+        .into();
 
     let (proof_ucan_cid, proof_token) = make_proof(
         &identities.alice_key,
         identities.bob_did.clone(),
         UcanOptions {
-            capabilities: vec![send_email_as_alice.clone()],
-            ..Default::default()
+            capabilities: vec![
+                send_email_as_alice.clone(),
+                send_email_as_marketing,
+                send_email_as_support,
+            ],
+            ..UcanOptions::for_version(version)
         },
     )
-    .await;
+    .await?;
 
     make_fixture(
-        String::from("UCAN has a delegated capability"),
+        String::from("UCAN claims a subset of the proof capabilities"),
         &identities.bob_key,
         identities.mallory_did.clone(),
         UcanOptions {
             capabilities: vec![send_email_as_alice],
-            proofs: vec![proof_ucan_cid.clone()],
-            ..Default::default()
+            proofs: vec![proof_ucan_cid],
+            ..UcanOptions::for_version(version)
         },
-        HashMap::from([(proof_ucan_cid, proof_token)]),
+        vec![proof_token],
     )
     .await
 }
 
-async fn merges_delegated_capabilities(
+// Bob claims alice's delegated resource alongside a resource bob owns, which
+// needs no proof
+async fn independent_resource_grants(
     identities: Rc<Identities<Ed25519KeyMaterial>>,
-) -> VerifyFixture {
+    version: &str,
+) -> Result<VerifyFixture> {
     let send_email_as_alice: Capability = EMAIL_SEMANTICS
         .parse("mailto:alice@email.com", "email/send", None)
         .unwrap()
         .into();
+    let send_email_as_bob: Capability = EMAIL_SEMANTICS
+        .parse("mailto:bob@email.com", "email/send", None)
+        .unwrap()
+        .into();
 
-    let (alice_proof_ucan_cid, alice_proof_token) = make_proof(
+    let (proof_ucan_cid, proof_token) = make_proof(
         &identities.alice_key,
         identities.bob_did.clone(),
         UcanOptions {
             capabilities: vec![send_email_as_alice.clone()],
-            ..Default::default()
+            ..UcanOptions::for_version(version)
         },
     )
-    .await;
+    .await?;
 
-    let send_email_as_marketing: Capability = EMAIL_SEMANTICS
-        .parse("mailto:marketing@email.com", "email/send", None)
+    make_fixture(
+        String::from("UCAN claims a delegated capability and an owned capability"),
+        &identities.bob_key,
+        identities.mallory_did.clone(),
+        UcanOptions {
+            capabilities: vec![send_email_as_alice, send_email_as_bob],
+            proofs: vec![proof_ucan_cid],
+            ..UcanOptions::for_version(version)
+        },
+        vec![proof_token],
+    )
+    .await
+}
+
+// Proofs are matched to prf by decoded CID, not by string. The proof's CID
+// is base32 by default, while prf lists the same CID in base58btc.
+async fn proof_cid_encoding_equivalence(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<VerifyFixture> {
+    let (proof_ucan_cid, proof_token) = make_proof(
+        &identities.alice_key,
+        identities.bob_did.clone(),
+        UcanOptions {
+            ..UcanOptions::for_version(version)
+        },
+    )
+    .await?;
+
+    let base58_proof_ucan_cid = Cid::from_str(&proof_ucan_cid)
+        .unwrap()
+        .to_string_of_base(Base::Base58Btc)
+        .unwrap();
+
+    Ok(make_fixture(
+        String::from("UCAN proof CID matches in a different multibase encoding"),
+        &identities.bob_key,
+        identities.mallory_did.clone(),
+        UcanOptions {
+            proofs: vec![base58_proof_ucan_cid],
+            ..UcanOptions::for_version(version)
+        },
+        vec![proof_token],
+    )
+    .await?
+    .with_conformance(Conformance::Should))
+}
+
+// alice delegates to bob, bob to mallory, and mallory back to alice, each
+// narrowing the WNFS path. The capability must be traced through every
+// proof to its root.
+async fn three_hop_delegation_chain(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<VerifyFixture> {
+    let hops = [
+        ("wnfs://alice.example.com/public", "wnfs/overwrite"),
+        ("wnfs://alice.example.com/public/photos", "wnfs/overwrite"),
+        (
+            "wnfs://alice.example.com/public/photos/vacation",
+            "wnfs/append",
+        ),
+    ]
+    .iter()
+    .map(|(resource, ability)| {
+        let capability: Capability = WNFS_SEMANTICS
+            .parse(resource, ability, None)
+            .unwrap()
+            .into();
+
+        vec![capability]
+    })
+    .collect();
+
+    let (leaf_token, proofs) = make_proof_chain(identities.as_ref(), hops, version).await?;
+
+    fixture_from_ucan(
+        String::from("UCAN has a three-hop delegation chain"),
+        Ucan::from_str(&leaf_token)?,
+        proofs,
+    )
+}
+
+// alice delegates to bob, bob to mallory, and mallory to victor, so every
+// hop is between a different pair of parties
+async fn four_party_delegation_chain(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<VerifyFixture> {
+    let send_email_as_alice: Capability = EMAIL_SEMANTICS
+        .parse("mailto:alice@email.com", "email/send", None)
         .unwrap()
         .into();
 
-    let (marketing_proof_ucan_cid, marketing_proof_token) = make_proof(
+    let (alice_proof_ucan_cid, alice_proof_token) = make_proof(
         &identities.alice_key,
         identities.bob_did.clone(),
         UcanOptions {
-            capabilities: vec![send_email_as_marketing.clone()],
-            ..Default::default()
+            capabilities: vec![send_email_as_alice.clone()],
+            ..UcanOptions::for_version(version)
         },
     )
-    .await;
+    .await?;
 
-    make_fixture(
-        String::from("UCAN merges delegated capabilities"),
+    let (bob_proof_ucan_cid, bob_proof_token) = make_proof(
         &identities.bob_key,
         identities.mallory_did.clone(),
         UcanOptions {
-            capabilities: vec![send_email_as_alice, send_email_as_marketing],
-            proofs: vec![
-                alice_proof_ucan_cid.clone(),
-                marketing_proof_ucan_cid.clone(),
-            ],
-            ..Default::default()
+            capabilities: vec![send_email_as_alice.clone()],
+            proofs: vec![alice_proof_ucan_cid],
+            ..UcanOptions::for_version(version)
+        },
+    )
+    .await?;
+
+    make_fixture(
+        String::from("UCAN is delegated through four parties to victor"),
+        &identities.mallory_key,
+        identities.victor_did.clone(),
+        UcanOptions {
+            capabilities: vec![send_email_as_alice],
+            proofs: vec![bob_proof_ucan_cid],
+            ..UcanOptions::for_version(version)
+        },
+        vec![alice_proof_token, bob_proof_token],
+    )
+    .await
+}
+
+// alice delegates to herself, then onward to bob. The proof audience, the
+// proof issuer, and the token issuer are all alice, which is not a loop.
+async fn self_redelegation_chain(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<VerifyFixture> {
+    let send_email_as_alice: Capability = EMAIL_SEMANTICS
+        .parse("mailto:alice@email.com", "email/send", None)
+        .unwrap()
+        .into();
+
+    let (proof_ucan_cid, proof_token) = make_proof(
+        &identities.alice_key,
+        identities.alice_did.clone(),
+        UcanOptions {
+            capabilities: vec![send_email_as_alice.clone()],
+            ..UcanOptions::for_version(version)
+        },
+    )
+    .await?;
+
+    make_fixture(
+        String::from("UCAN issuer re-delegates to itself then onward"),
+        &identities.alice_key,
+        identities.bob_did.clone(),
+        UcanOptions {
+            capabilities: vec![send_email_as_alice],
+            proofs: vec![proof_ucan_cid],
+            ..UcanOptions::for_version(version)
+        },
+        vec![proof_token],
+    )
+    .await
+}
+
+// alice issues to her own DID
+async fn self_issued(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<VerifyFixture> {
+    let send_email_as_alice: Capability = EMAIL_SEMANTICS
+        .parse("mailto:alice@email.com", "email/send", None)
+        .unwrap()
+        .into();
+
+    make_fixture(
+        String::from("UCAN is issued to its own issuer"),
+        &identities.alice_key,
+        identities.alice_did.clone(),
+        UcanOptions {
+            capabilities: vec![send_email_as_alice],
+            ..UcanOptions::for_version(version)
+        },
+        vec![],
+    )
+    .await
+}
+
+// alice delegates to a second DID she controls, which delegates onward to
+// bob. The DIDs differ, so the proof audience must match the token issuer
+// as exact strings, not by controller.
+async fn loopback_audience(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<VerifyFixture> {
+    let alice_secondary_key = ed25519_key_from_base64(ALICE_SECONDARY_BASE64_KEY)
+        .context("Could not decode alice's secondary key")?;
+    let alice_secondary_did = alice_secondary_key.get_did().await?;
+
+    let send_email_as_alice: Capability = EMAIL_SEMANTICS
+        .parse("mailto:alice@email.com", "email/send", None)
+        .unwrap()
+        .into();
+
+    let (proof_ucan_cid, proof_token) = make_proof(
+        &identities.alice_key,
+        alice_secondary_did,
+        UcanOptions {
+            capabilities: vec![send_email_as_alice.clone()],
+            ..UcanOptions::for_version(version)
+        },
+    )
+    .await?;
+
+    make_fixture(
+        String::from("UCAN is delegated through a second DID of the issuer"),
+        &alice_secondary_key,
+        identities.bob_did.clone(),
+        UcanOptions {
+            capabilities: vec![send_email_as_alice],
+            proofs: vec![proof_ucan_cid],
+            ..UcanOptions::for_version(version)
         },
-        HashMap::from([
-            (alice_proof_ucan_cid, alice_proof_token),
-            (marketing_proof_ucan_cid, marketing_proof_token),
-        ]),
+        vec![proof_token],
     )
     .await
 }
 
-async fn caveats_equal(identities: Rc<Identities<Ed25519KeyMaterial>>) -> VerifyFixture {
+async fn caveats_equal(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<VerifyFixture> {
     let caveat = json!({"templates": ["newsletter"]});
     let send_newsletter_as_alice: Capability = EMAIL_SEMANTICS
         .parse("mailto:alice@email.com", "email/send", Some(&caveat))
@@ -400,10 +1007,10 @@ async fn caveats_equal(identities: Rc<Identities<Ed25519KeyMaterial>>) -> Verify
         identities.bob_did.clone(),
         UcanOptions {
             capabilities: vec![send_newsletter_as_alice.clone()],
-            ..Default::default()
+            ..UcanOptions::for_version(version)
         },
     )
-    .await;
+    .await?;
 
     make_fixture(
         String::from("UCAN capability caveats equal to proof caveats"),
@@ -411,15 +1018,18 @@ async fn caveats_equal(identities: Rc<Identities<Ed25519KeyMaterial>>) -> Verify
         identities.mallory_did.clone(),
         UcanOptions {
             capabilities: vec![send_newsletter_as_alice],
-            proofs: vec![proof_ucan_cid.clone()],
-            ..Default::default()
+            proofs: vec![proof_ucan_cid],
+            ..UcanOptions::for_version(version)
         },
-        HashMap::from([(proof_ucan_cid, proof_token)]),
+        vec![proof_token],
     )
     .await
 }
 
-async fn caveats_attenuate(identities: Rc<Identities<Ed25519KeyMaterial>>) -> VerifyFixture {
+async fn caveats_attenuate(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<VerifyFixture> {
     let full_caveat = json!({"templates": ["newsletter", "marketing"]});
     let reduced_scope_caveat = json!({"templates": ["newsletter"]});
 
@@ -445,10 +1055,10 @@ async fn caveats_attenuate(identities: Rc<Identities<Ed25519KeyMaterial>>) -> Ve
         identities.bob_did.clone(),
         UcanOptions {
             capabilities: vec![send_email_as_marketing],
-            ..Default::default()
+            ..UcanOptions::for_version(version)
         },
     )
-    .await;
+    .await?;
 
     make_fixture(
         String::from("UCAN capability attenuates existing caveats"),
@@ -456,17 +1066,18 @@ async fn caveats_attenuate(identities: Rc<Identities<Ed25519KeyMaterial>>) -> Ve
         identities.mallory_did.clone(),
         UcanOptions {
             capabilities: vec![send_newsletter],
-            proofs: vec![proof_ucan_cid.clone()],
-            ..Default::default()
+            proofs: vec![proof_ucan_cid],
+            ..UcanOptions::for_version(version)
         },
-        HashMap::from([(proof_ucan_cid, proof_token)]),
+        vec![proof_token],
     )
     .await
 }
 
 async fn caveats_attenuate_from_no_caveats(
     identities: Rc<Identities<Ed25519KeyMaterial>>,
-) -> VerifyFixture {
+    version: &str,
+) -> Result<VerifyFixture> {
     let send_email_as_marketing: Capability = EMAIL_SEMANTICS
         .parse("mailto:marketing@email.com", "email/send", None)
         .unwrap()
@@ -477,10 +1088,10 @@ async fn caveats_attenuate_from_no_caveats(
         identities.bob_did.clone(),
         UcanOptions {
             capabilities: vec![send_email_as_marketing],
-            ..Default::default()
+            ..UcanOptions::for_version(version)
         },
     )
-    .await;
+    .await?;
 
     let caveat = json!({"templates": ["newsletter"]});
     let send_newsletter: Capability = EMAIL_SEMANTICS
@@ -494,26 +1105,922 @@ async fn caveats_attenuate_from_no_caveats(
         identities.mallory_did.clone(),
         UcanOptions {
             capabilities: vec![send_newsletter],
-            proofs: vec![proof_ucan_cid.clone()],
-            ..Default::default()
+            proofs: vec![proof_ucan_cid],
+            ..UcanOptions::for_version(version)
         },
-        HashMap::from([(proof_ucan_cid, proof_token)]),
+        vec![proof_token],
+    )
+    .await
+}
+
+// Caveat arrays compare as sets, so listing the same caveats in a
+// different order delegates the same capability
+async fn caveat_order_independent(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<VerifyFixture> {
+    let newsletter_caveat = json!({"templates": ["newsletter"]});
+    let send_newsletter: Capability = EMAIL_SEMANTICS
+        .parse(
+            "mailto:marketing@email.com",
+            "email/send",
+            Some(&newsletter_caveat),
+        )
+        .unwrap()
+        .into();
+
+    let marketing_caveat = json!({"templates": ["marketing"]});
+    let send_marketing: Capability = EMAIL_SEMANTICS
+        .parse(
+            "mailto:marketing@email.com",
+            "email/send",
+            Some(&marketing_caveat),
+        )
+        .unwrap()
+        .into();
+
+    let (proof_ucan_cid, proof_token) = make_proof(
+        &identities.alice_key,
+        identities.bob_did.clone(),
+        UcanOptions {
+            capabilities: vec![send_newsletter.clone(), send_marketing.clone()],
+            ..UcanOptions::for_version(version)
+        },
+    )
+    .await?;
+
+    Ok(make_fixture(
+        String::from("UCAN capability caveats match proof caveats in a different order"),
+        &identities.bob_key,
+        identities.mallory_did.clone(),
+        UcanOptions {
+            capabilities: vec![send_marketing, send_newsletter],
+            proofs: vec![proof_ucan_cid],
+            ..UcanOptions::for_version(version)
+        },
+        vec![proof_token],
+    )
+    .await?
+    .with_conformance(Conformance::Should))
+}
+
+// Each caveat object is an alternative the holder may act under, so
+// keeping some of the proof's caveat objects and dropping others narrows it
+async fn caveats_select_subset(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<VerifyFixture> {
+    let newsletter_caveat = json!({"templates": ["newsletter"]});
+    let send_newsletter: Capability = EMAIL_SEMANTICS
+        .parse(
+            "mailto:marketing@email.com",
+            "email/send",
+            Some(&newsletter_caveat),
+        )
+        .unwrap()
+        .into();
+
+    let marketing_caveat = json!({"templates": ["marketing"]});
+    let send_marketing: Capability = EMAIL_SEMANTICS
+        .parse(
+            "mailto:marketing@email.com",
+            "email/send",
+            Some(&marketing_caveat),
+        )
+        .unwrap()
+        .into();
+
+    let (proof_ucan_cid, proof_token) = make_proof(
+        &identities.alice_key,
+        identities.bob_did.clone(),
+        UcanOptions {
+            capabilities: vec![send_newsletter.clone(), send_marketing],
+            ..UcanOptions::for_version(version)
+        },
+    )
+    .await?;
+
+    make_fixture(
+        String::from("UCAN capability keeps a subset of the proof caveats"),
+        &identities.bob_key,
+        identities.mallory_did.clone(),
+        UcanOptions {
+            capabilities: vec![send_newsletter],
+            proofs: vec![proof_ucan_cid],
+            ..UcanOptions::for_version(version)
+        },
+        vec![proof_token],
+    )
+    .await
+}
+
+// The empty caveat {} places no restriction, so it is the most general
+// caveat. Only a proof that also has {} delegates it; narrowing a
+// restricted caveat to {} is the disprove fixture "UCAN escalates to no
+// caveats".
+async fn no_caveats_delegated_by_no_caveats(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<VerifyFixture> {
+    let send_email_as_marketing: Capability = EMAIL_SEMANTICS
+        .parse("mailto:marketing@email.com", "email/send", None)
+        .unwrap()
+        .into();
+
+    let (proof_ucan_cid, proof_token) = make_proof(
+        &identities.alice_key,
+        identities.bob_did.clone(),
+        UcanOptions {
+            capabilities: vec![send_email_as_marketing.clone()],
+            ..UcanOptions::for_version(version)
+        },
+    )
+    .await?;
+
+    make_fixture(
+        String::from("UCAN capability with no caveats is delegated by a proof with no caveats"),
+        &identities.bob_key,
+        identities.mallory_did.clone(),
+        UcanOptions {
+            capabilities: vec![send_email_as_marketing],
+            proofs: vec![proof_ucan_cid],
+            ..UcanOptions::for_version(version)
+        },
+        vec![proof_token],
+    )
+    .await
+}
+
+async fn capability_and_time_attenuate(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<VerifyFixture> {
+    let send_email_as_alice: Capability = EMAIL_SEMANTICS
+        .parse("mailto:alice@email.com", "email/send", None)
+        .unwrap()
+        .into();
+
+    let (proof_ucan_cid, proof_token) = make_proof(
+        &identities.alice_key,
+        identities.bob_did.clone(),
+        UcanOptions {
+            capabilities: vec![send_email_as_alice.clone()],
+            not_before: Some(1),
+            expiration: Some(14069142000),
+            ..UcanOptions::for_version(version)
+        },
+    )
+    .await?;
+
+    make_fixture(
+        String::from("UCAN claims a delegated capability within a narrower time window"),
+        &identities.bob_key,
+        identities.mallory_did.clone(),
+        UcanOptions {
+            capabilities: vec![send_email_as_alice],
+            not_before: Some(2),
+            expiration: Some(9246211200),
+            proofs: vec![proof_ucan_cid],
+            ..UcanOptions::for_version(version)
+        },
+        vec![proof_token],
+    )
+    .await
+}
+
+// Per RFC 3986 the scheme is case-insensitive, so MAILTO: matches mailto:.
+// Everything after the scheme is compared exactly.
+async fn resource_scheme_case_insensitive(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<VerifyFixture> {
+    // Built directly because URL parsing would lowercase the scheme
+    let send_email_as_alice_uppercase = Capability::new(
+        String::from("MAILTO:alice@email.com"),
+        String::from("email/send"),
+        json!({}),
+    );
+
+    let send_email_as_alice: Capability = EMAIL_SEMANTICS
+        .parse("mailto:alice@email.com", "email/send", None)
+        .unwrap()
+        .into();
+
+    let (proof_ucan_cid, proof_token) = make_proof(
+        &identities.alice_key,
+        identities.bob_did.clone(),
+        UcanOptions {
+            capabilities: vec![send_email_as_alice_uppercase],
+            ..UcanOptions::for_version(version)
+        },
+    )
+    .await?;
+
+    Ok(make_fixture(
+        String::from("UCAN capability resource scheme matches proof case-insensitively"),
+        &identities.bob_key,
+        identities.mallory_did.clone(),
+        UcanOptions {
+            capabilities: vec![send_email_as_alice],
+            proofs: vec![proof_ucan_cid],
+            ..UcanOptions::for_version(version)
+        },
+        vec![proof_token],
+    )
+    .await?
+    .with_conformance(Conformance::Should))
+}
+
+// Resources compare after NFC normalization, so a proof naming a
+// decomposed "e" + U+0301 grants the same resource as a composed U+00E9
+async fn resource_unicode_normalization(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<VerifyFixture> {
+    // Built directly because URL parsing would percent-encode the resource
+    let send_email_as_jose_nfd = Capability::new(
+        String::from("mailto:jose\u{0301}@email.com"),
+        String::from("email/send"),
+        json!({}),
+    );
+    let send_email_as_jose_nfc = Capability::new(
+        String::from("mailto:jos\u{00e9}@email.com"),
+        String::from("email/send"),
+        json!({}),
+    );
+
+    let (proof_ucan_cid, proof_token) = make_proof(
+        &identities.alice_key,
+        identities.bob_did.clone(),
+        UcanOptions {
+            capabilities: vec![send_email_as_jose_nfd],
+            ..UcanOptions::for_version(version)
+        },
+    )
+    .await?;
+
+    Ok(make_fixture(
+        String::from("UCAN capability resource matches proof after Unicode normalization"),
+        &identities.bob_key,
+        identities.mallory_did.clone(),
+        UcanOptions {
+            capabilities: vec![send_email_as_jose_nfc],
+            proofs: vec![proof_ucan_cid],
+            ..UcanOptions::for_version(version)
+        },
+        vec![proof_token],
+    )
+    .await?
+    .with_conformance(Conformance::Should))
+}
+
+async fn wnfs_capability_delegated(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<VerifyFixture> {
+    let append_to_photos: Capability = WNFS_SEMANTICS
+        .parse(
+            "wnfs://alice.example.com/public/photos",
+            "wnfs/append",
+            None,
+        )
+        .unwrap()
+        .into();
+
+    let (proof_ucan_cid, proof_token) = make_proof(
+        &identities.alice_key,
+        identities.bob_did.clone(),
+        UcanOptions {
+            capabilities: vec![append_to_photos.clone()],
+            ..UcanOptions::for_version(version)
+        },
+    )
+    .await?;
+
+    make_fixture(
+        String::from("UCAN has a delegated WNFS capability"),
+        &identities.bob_key,
+        identities.mallory_did.clone(),
+        UcanOptions {
+            capabilities: vec![append_to_photos],
+            proofs: vec![proof_ucan_cid],
+            ..UcanOptions::for_version(version)
+        },
+        vec![proof_token],
+    )
+    .await
+}
+
+// WNFS paths contain their sub-paths and wnfs/overwrite implies
+// wnfs/append, so the claim narrows both the path and the ability
+async fn wnfs_capability_attenuates(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<VerifyFixture> {
+    let overwrite_photos: Capability = WNFS_SEMANTICS
+        .parse(
+            "wnfs://alice.example.com/public/photos",
+            "wnfs/overwrite",
+            None,
+        )
+        .unwrap()
+        .into();
+    let append_to_vacation_photos: Capability = WNFS_SEMANTICS
+        .parse(
+            "wnfs://alice.example.com/public/photos/vacation",
+            "wnfs/append",
+            None,
+        )
+        .unwrap()
+        .into();
+
+    let (proof_ucan_cid, proof_token) = make_proof(
+        &identities.alice_key,
+        identities.bob_did.clone(),
+        UcanOptions {
+            capabilities: vec![overwrite_photos],
+            ..UcanOptions::for_version(version)
+        },
+    )
+    .await?;
+
+    make_fixture(
+        String::from("UCAN attenuates a WNFS capability to a sub-path"),
+        &identities.bob_key,
+        identities.mallory_did.clone(),
+        UcanOptions {
+            capabilities: vec![append_to_vacation_photos],
+            proofs: vec![proof_ucan_cid],
+            ..UcanOptions::for_version(version)
+        },
+        vec![proof_token],
+    )
+    .await
+}
+
+// Abilities may have more than two segments, and a trailing wildcard
+// subsumes the segments it replaces
+async fn multi_segment_ability(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<VerifyFixture> {
+    let any_file_ability: Capability = SEGMENT_SEMANTICS
+        .parse("https://example.com/files", "crud/file/*", None)
+        .unwrap()
+        .into();
+    let read_file: Capability = SEGMENT_SEMANTICS
+        .parse("https://example.com/files", "crud/file/read", None)
+        .unwrap()
+        .into();
+
+    let (proof_ucan_cid, proof_token) = make_proof(
+        &identities.alice_key,
+        identities.bob_did.clone(),
+        UcanOptions {
+            capabilities: vec![any_file_ability],
+            ..UcanOptions::for_version(version)
+        },
+    )
+    .await?;
+
+    make_fixture(
+        String::from("UCAN has a multi-segment ability delegated by a wildcard"),
+        &identities.bob_key,
+        identities.mallory_did.clone(),
+        UcanOptions {
+            capabilities: vec![read_file],
+            proofs: vec![proof_ucan_cid],
+            ..UcanOptions::for_version(version)
+        },
+        vec![proof_token],
     )
     .await
 }
 
 // FACTS
 
-async fn has_fact(identities: Rc<Identities<Ed25519KeyMaterial>>) -> VerifyFixture {
+async fn has_fact(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<VerifyFixture> {
     make_fixture(
         String::from("UCAN has a fact"),
         &identities.alice_key,
         identities.bob_did.clone(),
         UcanOptions {
             facts: BTreeMap::from([(String::from("challenge"), json!("abcdef"))]),
-            ..Default::default()
+            ..UcanOptions::for_version(version)
+        },
+        vec![],
+    )
+    .await
+}
+
+async fn facts_of_each_type(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<VerifyFixture> {
+    make_fixture(
+        String::from("UCAN has facts of every JSON type"),
+        &identities.alice_key,
+        identities.bob_did.clone(),
+        UcanOptions {
+            facts: BTreeMap::from([
+                (String::from("challenge"), json!("abcdef")),
+                (String::from("count"), json!(42)),
+                (String::from("ratio"), json!(0.5)),
+                (String::from("verified"), json!(true)),
+                (String::from("revoked"), json!(null)),
+                (String::from("tags"), json!(["email", 7, false])),
+                (
+                    String::from("profile"),
+                    json!({ "name": "alice", "address": { "city": "Lisbon" } }),
+                ),
+            ]),
+            ..UcanOptions::for_version(version)
+        },
+        vec![],
+    )
+    .await
+}
+
+// The caveat names a key in fct, but the reference is opaque. Neither the
+// generator nor a verifier resolves it, so the UCAN is valid as written.
+async fn caveat_references_fact(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<VerifyFixture> {
+    let caveat = json!({"fact": "challenge"});
+    let send_email_as_alice: Capability = EMAIL_SEMANTICS
+        .parse("mailto:alice@email.com", "email/send", Some(&caveat))
+        .unwrap()
+        .into();
+
+    make_fixture(
+        String::from("UCAN has a capability caveat referencing a fact"),
+        &identities.alice_key,
+        identities.bob_did.clone(),
+        UcanOptions {
+            capabilities: vec![send_email_as_alice],
+            facts: BTreeMap::from([(String::from("challenge"), json!("abcdef"))]),
+            ..UcanOptions::for_version(version)
+        },
+        vec![],
+    )
+    .await
+}
+
+// EXTREMES
+
+async fn minimal_ucan(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<VerifyFixture> {
+    make_fixture(
+        String::from("UCAN omits every optional field"),
+        &identities.alice_key,
+        identities.bob_did.clone(),
+        UcanOptions {
+            ..UcanOptions::for_version(version)
+        },
+        vec![],
+    )
+    .await
+}
+
+async fn maximal_ucan(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<VerifyFixture> {
+    let send_email_as_alice: Capability = EMAIL_SEMANTICS
+        .parse("mailto:alice@email.com", "email/send", None)
+        .unwrap()
+        .into();
+
+    let caveat = json!({"templates": ["marketing"]});
+    let send_email_as_marketing: Capability = EMAIL_SEMANTICS
+        .parse("mailto:marketing@email.com", "email/send", Some(&caveat))
+        .unwrap()
+        .into();
+
+    let (proof_ucan_cid, proof_token) = make_proof(
+        &identities.alice_key,
+        identities.bob_did.clone(),
+        UcanOptions {
+            capabilities: vec![send_email_as_alice.clone(), send_email_as_marketing.clone()],
+            not_before: Some(1),
+            expiration: Some(9246211200),
+            ..UcanOptions::for_version(version)
+        },
+    )
+    .await?;
+
+    make_fixture(
+        String::from("UCAN populates every field"),
+        &identities.bob_key,
+        identities.mallory_did.clone(),
+        UcanOptions {
+            capabilities: vec![send_email_as_alice, send_email_as_marketing],
+            not_before: Some(1),
+            expiration: Some(9246211200),
+            facts: BTreeMap::from([(String::from("challenge"), json!("abcdef"))]),
+            proofs: vec![proof_ucan_cid],
+            nonce: Some(String::from("6f2mUhTqJOxZ4cYp")),
+            ..UcanOptions::for_version(version)
+        },
+        vec![proof_token],
+    )
+    .await
+}
+
+async fn full_payload_with_proof(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<VerifyFixture> {
+    let (proof_ucan_cid, proof_token) = make_proof(
+        &identities.alice_key,
+        identities.bob_did.clone(),
+        UcanOptions {
+            ..UcanOptions::for_version(version)
+        },
+    )
+    .await?;
+
+    make_fixture(
+        String::from("UCAN has a nonce, a fact, and a proof"),
+        &identities.bob_key,
+        identities.mallory_did.clone(),
+        UcanOptions {
+            facts: BTreeMap::from([(String::from("challenge"), json!("abcdef"))]),
+            proofs: vec![proof_ucan_cid],
+            nonce: Some(String::from("Yk3oWq8dAkR1nVbe")),
+            ..UcanOptions::for_version(version)
+        },
+        vec![proof_token],
+    )
+    .await
+}
+
+// The payload is signed once as `ucan` serializes it and once with its keys
+// reversed. Both verify, but a CID hashes the bytes, so each has its own.
+async fn payload_key_order(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<VerifyFixture> {
+    let send_email_as_alice: Capability = EMAIL_SEMANTICS
+        .parse("mailto:alice@email.com", "email/send", None)
+        .unwrap()
+        .into();
+
+    let mut fixture = make_fixture(
+        String::from("UCAN payload key order changes the CID but not validity"),
+        &identities.alice_key,
+        identities.bob_did.clone(),
+        UcanOptions {
+            capabilities: vec![send_email_as_alice],
+            expiration: Some(9246211200),
+            facts: BTreeMap::from([(String::from("challenge"), json!("abcdef"))]),
+            nonce: Some(String::from("Qe7bNs1XvK4tZp2d")),
+            ..UcanOptions::for_version(version)
+        },
+        vec![],
+    )
+    .await?;
+
+    let token = fixture.inputs.token.clone();
+    let reordered = reverse_payload_keys(&token, identities.alice_key.clone())?;
+
+    fixture.encodings = Some(
+        [token, reordered]
+            .into_iter()
+            .map(|token| {
+                Ok(Encoding {
+                    cid: token_to_cid(&token)?,
+                    token,
+                })
+            })
+            .collect::<Result<_>>()?,
+    );
+
+    Ok(fixture)
+}
+
+// The nonce only makes a token unique. Two tokens that differ only in
+// their nonce are both valid, with different CIDs.
+async fn nonce_uniqueness_pair(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<VerifyFixture> {
+    let send_email_as_alice: Capability = EMAIL_SEMANTICS
+        .parse("mailto:alice@email.com", "email/send", None)
+        .unwrap()
+        .into();
+
+    let options = |nonce: &str| UcanOptions {
+        capabilities: vec![send_email_as_alice.clone()],
+        nonce: Some(String::from(nonce)),
+        ..UcanOptions::for_version(version)
+    };
+
+    let mut fixture = make_fixture(
+        String::from("UCANs differing only in nonce are both valid"),
+        &identities.alice_key,
+        identities.bob_did.clone(),
+        options("Qe7bNs1XvK4tZp2d"),
+        vec![],
+    )
+    .await?;
+
+    let other_nonce = sign_with_options(
+        &identities.alice_key,
+        identities.bob_did.clone(),
+        options("Lm3wYc8RfJ0hTs5u"),
+    )
+    .await?;
+    let tokens = [fixture.inputs.token.clone(), Ucan::encode(&other_nonce)?];
+
+    fixture.encodings = Some(
+        tokens
+            .into_iter()
+            .map(|token| {
+                Ok(Encoding {
+                    cid: token_to_cid(&token)?,
+                    token,
+                })
+            })
+            .collect::<Result<_>>()?,
+    );
+
+    Ok(fixture)
+}
+
+// JWT claims that are not understood must be ignored (RFC 7519, section
+// 4), so a payload field outside the UCAN spec does not invalidate it
+async fn unknown_payload_field(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<VerifyFixture> {
+    let send_email_as_alice: Capability = EMAIL_SEMANTICS
+        .parse("mailto:alice@email.com", "email/send", None)
+        .unwrap()
+        .into();
+
+    let mut fixture = make_fixture(
+        String::from("UCAN payload contains unknown field"),
+        &identities.alice_key,
+        identities.bob_did.clone(),
+        UcanOptions {
+            capabilities: vec![send_email_as_alice],
+            ..UcanOptions::for_version(version)
+        },
+        vec![],
+    )
+    .await?
+    .with_conformance(Conformance::Should);
+
+    let token = add_field(
+        &fixture.inputs.token,
+        "payload",
+        "xyz",
+        json!("unknown"),
+        identities.alice_key.clone(),
+    )?;
+    fixture.assertions = ucan_to_assertions(Ucan::from_str(&token)?);
+    fixture.inputs.token = token;
+
+    Ok(fixture)
+}
+
+// KEY TYPES
+
+// RSA did:keys embed the whole 2048-bit public key, so the iss field is
+// much larger than for the other key types
+async fn rsa_signed(
+    identities: Rc<Identities<RsaKeyMaterial>>,
+    version: &str,
+) -> Result<VerifyFixture> {
+    let send_email_as_alice: Capability = EMAIL_SEMANTICS
+        .parse("mailto:alice@email.com", "email/send", None)
+        .unwrap()
+        .into();
+
+    Ok(make_fixture(
+        String::from("UCAN is signed with RS256"),
+        &identities.alice_key,
+        identities.bob_did.clone(),
+        UcanOptions {
+            capabilities: vec![send_email_as_alice],
+            ..UcanOptions::for_version(version)
+        },
+        vec![],
+    )
+    .await?
+    .with_conformance(Conformance::Should))
+}
+
+async fn rsa_has_delegated_capability(
+    identities: Rc<Identities<RsaKeyMaterial>>,
+    version: &str,
+) -> Result<VerifyFixture> {
+    let send_email_as_alice: Capability = EMAIL_SEMANTICS
+        .parse("mailto:alice@email.com", "email/send", None)
+        .unwrap()
+        .into();
+
+    let (proof_ucan_cid, proof_token) = make_proof(
+        &identities.alice_key,
+        identities.bob_did.clone(),
+        UcanOptions {
+            capabilities: vec![send_email_as_alice.clone()],
+            ..UcanOptions::for_version(version)
+        },
+    )
+    .await?;
+
+    Ok(make_fixture(
+        String::from("UCAN signed with RS256 has a capability delegated with RS256"),
+        &identities.bob_key,
+        identities.mallory_did.clone(),
+        UcanOptions {
+            capabilities: vec![send_email_as_alice],
+            proofs: vec![proof_ucan_cid],
+            ..UcanOptions::for_version(version)
+        },
+        vec![proof_token],
+    )
+    .await?
+    .with_conformance(Conformance::Should))
+}
+
+async fn secp256k1_signed(
+    identities: Rc<Identities<Secp256k1KeyMaterial>>,
+    version: &str,
+) -> Result<VerifyFixture> {
+    let send_email_as_alice: Capability = EMAIL_SEMANTICS
+        .parse("mailto:alice@email.com", "email/send", None)
+        .unwrap()
+        .into();
+
+    Ok(make_fixture(
+        String::from("UCAN is signed with ES256K"),
+        &identities.alice_key,
+        identities.bob_did.clone(),
+        UcanOptions {
+            capabilities: vec![send_email_as_alice],
+            ..UcanOptions::for_version(version)
+        },
+        vec![],
+    )
+    .await?
+    .with_conformance(Conformance::Should))
+}
+
+// WebCrypto implementations sign with P-256, so browsers rely on ES256
+async fn p256_signed(
+    identities: Rc<Identities<P256KeyMaterial>>,
+    version: &str,
+) -> Result<VerifyFixture> {
+    let send_email_as_alice: Capability = EMAIL_SEMANTICS
+        .parse("mailto:alice@email.com", "email/send", None)
+        .unwrap()
+        .into();
+
+    Ok(make_fixture(
+        String::from("UCAN is signed with ES256"),
+        &identities.alice_key,
+        identities.bob_did.clone(),
+        UcanOptions {
+            capabilities: vec![send_email_as_alice],
+            ..UcanOptions::for_version(version)
+        },
+        vec![],
+    )
+    .await?
+    .with_conformance(Conformance::Should))
+}
+
+// The signature verifies with the key listed in alice's did:web document,
+// which `resolved_dids` gives as her did:key
+#[cfg(feature = "did-web")]
+async fn did_web_issuer(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    version: &str,
+) -> Result<VerifyFixture> {
+    let mut fixture = make_fixture(
+        String::from("UCAN is issued by a did:web DID"),
+        &identities.alice_key,
+        identities.bob_did.clone(),
+        UcanOptions {
+            ..UcanOptions::for_version(version)
+        },
+        vec![],
+    )
+    .await?;
+
+    let token = super::mutate::mutate_field(
+        &fixture.inputs.token,
+        "payload",
+        "iss",
+        json!(ALICE_DID_WEB),
+        identities.alice_key.clone(),
+    )?;
+    fixture.assertions = ucan_to_assertions(Ucan::from_str(&token)?);
+    fixture.inputs.token = token;
+    fixture.resolved_dids = Some(BTreeMap::from([(
+        String::from(ALICE_DID_WEB),
+        identities.alice_did.clone(),
+    )]));
+
+    Ok(fixture.with_conformance(Conformance::May))
+}
+
+// Each hop is signed with a different key type: alice (Ed25519) delegates
+// to bob (RSA), who delegates to mallory (P-256), who issues the leaf
+async fn mixed_key_type_chain(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    rsa_identities: Rc<Identities<RsaKeyMaterial>>,
+    p256_identities: Rc<Identities<P256KeyMaterial>>,
+    version: &str,
+) -> Result<VerifyFixture> {
+    let send_email_as_alice: Capability = EMAIL_SEMANTICS
+        .parse("mailto:alice@email.com", "email/send", None)
+        .unwrap()
+        .into();
+
+    let (alice_proof_ucan_cid, alice_proof_token) = make_proof(
+        &identities.alice_key,
+        rsa_identities.bob_did.clone(),
+        UcanOptions {
+            capabilities: vec![send_email_as_alice.clone()],
+            ..UcanOptions::for_version(version)
+        },
+    )
+    .await?;
+
+    let (bob_proof_ucan_cid, bob_proof_token) = make_proof(
+        &rsa_identities.bob_key,
+        p256_identities.mallory_did.clone(),
+        UcanOptions {
+            capabilities: vec![send_email_as_alice.clone()],
+            proofs: vec![alice_proof_ucan_cid],
+            ..UcanOptions::for_version(version)
+        },
+    )
+    .await?;
+
+    Ok(make_fixture(
+        String::from("UCAN delegation chain spans Ed25519, RSA, and P-256 keys"),
+        &p256_identities.mallory_key,
+        identities.bob_did.clone(),
+        UcanOptions {
+            capabilities: vec![send_email_as_alice],
+            proofs: vec![bob_proof_ucan_cid],
+            ..UcanOptions::for_version(version)
+        },
+        vec![alice_proof_token, bob_proof_token],
+    )
+    .await?
+    .with_conformance(Conformance::Should))
+}
+
+// HIERARCHY
+
+async fn delegates_implied_ability(
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    semantics: &HierarchySemantics,
+    ability: &str,
+    implied: &str,
+    version: &str,
+) -> Result<VerifyFixture> {
+    let granted: Capability = semantics
+        .parse(HIERARCHY_RESOURCE, ability, None)
+        .unwrap()
+        .into();
+    let claimed: Capability = semantics
+        .parse(HIERARCHY_RESOURCE, implied, None)
+        .unwrap()
+        .into();
+
+    let (proof_ucan_cid, proof_token) = make_proof(
+        &identities.alice_key,
+        identities.bob_did.clone(),
+        UcanOptions {
+            capabilities: vec![granted],
+            ..UcanOptions::for_version(version)
+        },
+    )
+    .await?;
+
+    make_fixture(
+        format!("UCAN delegates {implied} from a proof granting {ability}"),
+        &identities.bob_key,
+        identities.mallory_did.clone(),
+        UcanOptions {
+            capabilities: vec![claimed],
+            proofs: vec![proof_ucan_cid],
+            ..UcanOptions::for_version(version)
         },
-        HashMap::new(),
+        vec![proof_token],
     )
     .await
 }
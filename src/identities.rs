@@ -1,6 +1,12 @@
-use crate::crypto::ed25519_key_from_base64;
+use crate::crypto::{
+    ed25519_key_from_base64, generate_ed25519_key, generate_ed25519_key_from_rng,
+    p256_key_from_base64, rsa_key_from_base64, secp256k1_key_from_base64, Secp256k1KeyMaterial,
+};
+use anyhow::{Context, Result};
+use rand::{rngs::StdRng, SeedableRng};
+use std::{collections::BTreeMap, fmt, rc::Rc};
 use ucan::crypto::KeyMaterial;
-use ucan_key_support::ed25519::Ed25519KeyMaterial;
+use ucan_key_support::{ed25519::Ed25519KeyMaterial, p256::P256KeyMaterial, rsa::RsaKeyMaterial};
 
 #[derive(Clone, Debug)]
 pub struct Identities<K>
@@ -10,29 +16,155 @@ where
     pub alice_key: K,
     pub bob_key: K,
     pub mallory_key: K,
+    pub victor_key: K,
 
     pub alice_did: String,
     pub bob_did: String,
     pub mallory_did: String,
+    pub victor_did: String,
 }
 
 pub const ALICE_BASE64_KEY: &str =
     "U+bzp2GaFQHso587iSFWPSeCzbSfn/CbNHEz7ilKRZ1UQMmMS7qq4UhTzKn3X9Nj/4xgrwa+UqhMOeo4Ki8JUw==";
 
+/// alice's did:key under `ALICE_BASE64_KEY`
+pub const ALICE_DID: &str = "did:key:z6Mkk89bC3JrVqKie71YEcc5M1SMVxuCgNx6zLZ8SYJsxALi";
+
+pub const BOB_BASE64_KEY: &str =
+    "G4+QCX1b3a45IzQsQd4gFMMe0UB1UOx9bCsh8uOiKLER69eAvVXvc8P2yc4Iig42Bv7JD2zJxhyFALyTKBHipg==";
+
+/// A did:web DID for alice, whose DID document lists her default Ed25519
+/// key. Fixtures issued under it say what it resolves to, so harnesses
+/// need no network access.
+#[cfg(feature = "did-web")]
+pub const ALICE_DID_WEB: &str = "did:web:alice.example.com";
+
+/// A second Ed25519 key held by alice, as after a key rotation
+pub const ALICE_SECONDARY_BASE64_KEY: &str =
+    "oaeE78JGkCql6ZIhmWE3+f6T5Xz+Ghze+QSYF9SsrKGUWmrSuq8Jmv56JCBV77nY6mmXJHEd3Wsb3jaChDFQJw==";
+
+/// 2048-bit RSA keys, PKCS#1 DER encoded
+pub const ALICE_RSA_BASE64_KEY: &str = "MIIEogIBAAKCAQEA3ayg6FUFgOyqP/4oSoL2yteLq0Hf/9C4Hr6tbyGLG/FvPHFgLCYFe4PGVm9vzzcxCIm/TP6Yn107jqnJIUQBsDekaY3KtAZYAng31dSMHOMi7EqTMt1ruOwzrpyYQ2b3FWtrqc/GTlfAPQZaAbYkTZXSPZzLrqanQfamXCnMiUluBSPa8dUbYXIU3kFVVkFQTrecs2okzo1Z+sksu2e4i9QW7e5ZLBfOO4mVAOn/qwOHE0DZ75DLnWyVno0WGNQ5P86Q9t3urYZjyW23A4o4wJYSBjnH4zZj6kJ3izbJKKP3dICC9LMyuoz2WkvIRTabroIhm0sOpqXKf0JbGwvCsQIDAQABAoIBAACN2j+T3MdGXU0LSmRj2ouP0oZZmvV0g0Ncg4eMOhjRZl/B0U65tqGJ3t0626u+Iu9ETF4/cOEmIInbWEe/ZE9UYP7BbFBRMjQzRh1hJ/5Z+rDbIgu6yTGO9p5Qeo+f9LNw4LXWzWcmUfqEqZJsFl2N2Ojr5u24mp3dNdhF5uKnA67+UpiliYDVcihVvpFf/LNhdB4SKp7DMLTVymLLzDyA6fI1czb/kaXZ/WV/h31xsN7G4Bf65Su9f8L1kWHTEDaWaadxhq0dnQ/wm8QzCNBiDLwL1woUWiO04ZSICJLpDFOT9Sj0vBHRpApQCp9J6A9dhwm3FgYP45yDfONVBW0CgYEA+Ctfy/njGNvjXzjjEOpcYFEYzc2pWH55PCKyhkptRZVdY3UjVUN3PVAKllp/w85wqupa/aUyrsg0OBzCNr+Sx7tYJtkGZLLfva1ye/DEYmzFdIY1/gfswVo9NVm0WgICNL68lqOTBChH7WL+ys9dJQnbHXwIbweCp9DeWtTuwmcCgYEA5Ks8dyfRzPiYLeoejKOvNQP9mBCfs/tZQ5bhkvkBaRNb6dqfhQf6ePub5cQaExK0WOJp47BR+fEQHQBLwu1+2aQZt9/k+bChuQndKELEF+iu7xctpZmQX/8jqdkUhWWR2AKItcxDAHf0y/lL7gnrT2RD5YftPoDw/6w7bL29kycCgYBEsNzHPNebHeIFjV1sZTJEhq3y6SYilH9CIeiAO0GbrQg4a0foZdAvbGRB0bLjo9STumKNKpiowEmT7oHjXYtnNXfk6NVYMhq0CbpSjAYPqEPPSQbQ1ai2rB+dEMNW1o99wf25RHWBBRVDo88am+MQSXYY5yf+c3m6sZmaUq3zqQKBgBBcVBIubitdKf42/wKO/40qgO1tWw/UjvlwBSmv2TL8aJodw0YQYRJg5jGVS0xMDU1CvdLFncflQILir4j91xKZVCG8yjQ3OAr2MrWbuU4AnuuKXAC6MQM9TP7tAjPdJYcv/zoKqEyTSJeDebRJT2W5xcXZaNcNALE6UHIiTTsHAoGAFlGX0BluDPd7aBj8pRtJ3Ia0PMo4ANUlZQpgqR6rhsIA63VEqNaGfSjESsd4NaS1jZRcivOMC6aKR2zZJ++uSdl7AAGHawhwIc/Bh6FOUqo4Uo14QGzGsoWQjnHQ5AYRGFloIDXfw9j30ZvvITXIN780/jKlVwuBMvDehdmMDsE=";
+pub const BOB_RSA_BASE64_KEY: &str = "MIIEpAIBAAKCAQEAwI63GEZIf6xVGtfdTGheFisUvM+WdgtqSIiGh0XEQJ9vz7+2xWB2DNlXdaCFWZ5/S1U+JK244q4pE/0lxChnOgQ8+xF/tbkFh6uEjKMQ5oX7/N3M2WZtBGPoZSR4cjQZLlHoQWsYJGjPTqk+B7TfnevcGXV+pt2B2aNhHso0Q0NS7jvyRUDQ1Uqrgmjt2AjhppVkKhuc3O43EHpFfcDPTF0oYJeRIYawNz4+fci0Yfg9wduvx7/vC85dTX8hWEITsGg7VD7t5F2+lQejwvv30WWWzz0uBXbUGGbwSmrW9OvCZkHeU9EwUP0NiyeSzqYPg87KFsgTO96APYkEA7YzBQIDAQABAoIBAAHMjSWTA1QwcH62zv1kdjvGMERPuv8Kvg+Q0vm9kaCG8ANklHKr1lgdY1J/UVYHUJCMHdYEfeQESFb3xQJ1qPoJfLMqmsmNKBUrgyprNZoc9ozXJ6kaUPqha+24YjWy8RwV+mtuDDfLqnz4jZ9PaQCi0DFR5mdTTd/4kkye2ALCDWifNP6egjXzQLIfVY6fZPlsnK0rJnYGJZTxf8hQrLwVcQntNgJv/BhvQyVDgee+kXWHwBuJJmnGIBlfyhSlrQA9JG4u/Hg/sAmApEbwuATZvQdz/DfWYJvetyjvRiIIvS54wwUvhbnXfL5nBAG9DFFMCFxJr91fl+UrAJOws7ECgYEA7c7OY95mKaZ7cH8buSpBHBXbmRNV7iCV3raZg0up9a3zqlfYrY3qrFGwlz/mF3iRSAhz5b9YGhX10ZyaPPbW5g/4TPkInXuLc7tTowZ+WHjN0+8zSFQmleB6HSRaw3T7vU6P8tdj2Cb4u83LAdFlMLqQWoQWDWSYrojrolj1iHkCgYEAz0m7kOY/G+P8UuWFR+3lCirCB43ARtmGYAGgPFDA6RsOa/GlXrozuniSzdzoGlxOVSJioXu77qnHNLouPrc66O1usI0Kz1Qkvysy50gi6aeMECSO/TjR9XMzbb1Qz5j+RTlVZg1Hiy/Sp9R1GtATe8MeYrqai7CdzLRdb5e88+0CgYEAk7LefSikSzobcMOBdH/J8KeivHBF7qBOdQT0U5eq9+wSarok2aZLGruh8k69mdZIZXTahtdQ4OLCym15XmPxFPLWRPhnYWhaNIckaxydXFWrgPYP49xgGrZRIchylvMRZ4Jj5W/hk16hHgOzEm37a0h0X8Op7qeMLNrdfvfBiAECgYBKTNCJkK5ATgkMoa6TaMc3TLo9wOaRvri2wv5vgDaVl5gkwvoWqUGPblaYqb0cbce6JlgaL+LeGkcINFANNNpN0YVijTu5sxx01YQqeKXjAKZoxuHmDC7d/wwJItnLf7hska91o/7n+h1VAQd0s+635umGVAK/XXBChsgTDt2wxQKBgQDm4uc3yPxnXuGyMt95Mn2KYSTT/y4IQtO+e6nrrgicI9f+bBOQMJ4xMM8QL5MFUgykv4RFz2OD8WogNr4jfACXDTnT8gZe31jX9zXaOAigjK0w1hyYDwAayEb/8zkj9l1DEvEA2Bo3PT0iPNpJqlzh/O6Pe3tYGBiVySHVTXos7w==";
+pub const MALLORY_RSA_BASE64_KEY: &str = "MIIEogIBAAKCAQEAzBYXRUeFZqChuFRXyUto4otGT0W7uVzeMoI3qaxoDgPdxv5udk7MFHyXCPD8A6Y8SyS3LmiXhm8mDWUzOSbYb0GtURUGLfXP2rBE2ekqyz/2IwdcB2LuuoBH3bhlyoFzpqYIevmbwuYYRh0ijTCmERGezoP22d3m2a4cfR8JjUjMLJEc07jKscvbF0QMt2UD7TztZv9ikr6ANj2S7JWLS79ha00iqAmcDltfXMNjahepN44g6Fsf6fHn2Vu+fG5dH3ATr7mlgIHK7+sF9x24sRlnpTn86D5WUnbM/vy2nX9Kfxf78MS27sw6z6sfSzYFQEIC565X5WBn8iZ1kwC4BwIDAQABAoIBAAQyoB/orLHDtA/ajEOzrWCbhMK0qHvTkS5wxzxSM4G2SqWBLjx4unHhbinc6xIYJT1Wb/4gYJ1/Q8Uaw8b1KZaAP1RfnlTrrW7OGVxyE1NC6Ykm6ZmtS0PCx7ejx1Q2/W38FEiTQcTsfqtShhdpXMEVH7MXu8gXbFwl3KgmL0gcocxElt2jwCRAA8+Upvq8Uy6FGxJrHPUqMtdy/ZQ+Fz9wdD3WiIcx1xu9uBbB98nVd7dfZOkp7Mkm1Aw246BZGhBnrhRZJ44/e+ZS89iUZzLEcVVgdl/uPRq5vgNhGo65Dckfh1qAa/Divd9NZuDZIx4rVdCsF0TllZu71tU6cOECgYEA8Fd5sKtXYmOf+gh33D9Be2GMTOVdoP9zDcWxDXvqciGuVEYFgWla5+yf6sgFFJzP0OsLlo6aNZNUTspqhqjoDy0qxYf+jvcTFMjtvWJthyNuOtSQ32Zl6BZ3/qzcPNaEDqMyJPC/9hNA4lyCBIs4JJQahYxG8jAy0e/vih4RLVMCgYEA2WHukPT8y9GPYYOx4BAzHyyuC7TfZ+J1Oem+dmSTpWhp/YR3zDdlVyem+jAUbHczzL6gHNsdOMHMd9DadYCVzIVPd+4ksmCYFYYpsL2pZiBZHUbS8V+Lgo/Gc7XO03K8FjkVm1y84gidDZ3Wphjd+qHQIjLH4hoMcg4YC+syv/0CgYAVIIto9VbkJj/M2eu3Z7QY5XVQ2STG30282zD6L3dnewB5FgKr4pgEJS/VkqmnqzU1d0P4gyaZKQ9TMcX1emmT0/Obk53T8XIy9Iq5iCp6o6mv8Dmf+b9hjIAV2houGulW5A+nYaZuOjkM7Pbc8WQJCx/dt3wRFB2TiNT0vjniUwKBgFZVWpYfdjbMZ5Fm6EIArQz4hbcIgqGTgtMZThdfInRS36Vb8ugcKBdRHDPI3JOnU99A5f4P168u6LG9NMXt55BucX1ECzb8venueqhC6wAwYLQL6MMMGzAjQcG6twWrT5/T1Az769gpa5Z41ULqJV7g3PR4DoJxrfeuOUaihmlRAoGADbECHXKyUZ65/YBlsnxSIRHTJSYRGBwloYg3qMa93Lp1bIyhq45TcUA9yn0nnMTlukYGFAP+1nDCy5H63CRHbC4Z8M0q46YEmQvOx1Y+zDzZpXXHrNrBGGLBrqzPqMkXrsy7CzjZFuLshzxGzZ/funBQBRD0wL+2CYttj74kcG0=";
+pub const VICTOR_RSA_BASE64_KEY: &str = "MIIEowIBAAKCAQEAjy4AG8WQe40kE89eRJIASo05YUl6KFC4zSKXUheLaLBPDyPJfCpehUejGaWyReu+5I5DzVDLXfK2pLL2yKQIlVISs5+JdBNFtUkAVwj/5bMfRs2D+Jel27V1P3GgSkECOy08nIX5BdTBU2CXFRlWWaIFYR9LrBLmVfwNvUAcwckB06eG1NNjA4IviPxyDYflPcjdPBC1FvTglUAGp2g7whBUBkrXtgLRIasSoUqH+ts2b1hLA/uK8GkfnXHnIPA58QnHiEnTnOuEXvOt8/ubpFBZTvj6MU3k9+bJMkhEVhqdkiANj4SUlhVfMwFc+w5cUiHaSYwxGtjtDrO0bSMY2wIDAQABAoIBAAMPj68ITStm/AN0ZmZ+3IS7eiOLR5I/D4RLHgMCP9ewFTCUqkT+PuEIhAMTWLlH7d/C7s4f0QkkHCVvMfleEFTo2T9/xJd3pgDXG6vApH/LkXqZAB47V69XtLbzfeK4ewy6zdXmw0kNoEhhAznPPdpSrVzDJ4H5MXVbGwwVp8e4tSH0wchw1eDqFYRKtlI0EF4PGouvCIicVj1YHUOjVu5GzZnqf7fSdRYO7ra0ineJnksPbZUF9L1J6I31gwuSCxYrTNkl5gD/UrOR/O9dN/L7QHGFo6D5reLemvsLtg+ij1nBfGNHAiZPE/c2Nmr5w8XyuF8l01k/Grx27YYjM4kCgYEAxOkMLZnsfP5Ctd1L4o/cE6qYV+YijVMb/0PB8f7R3IOln5cl7eqqCYzR2M0a+/4QHEzzeBougaerhFYYQkIdVesKHDHkWTfhJLcMfAz/b/R2v1CX/gKBXEamlGcjRLnQDreLe1OdOcmzjx5Vr2kpUWJA0q+7fZAEWu20i0WUX80CgYEAuiVIQXCCtLrikkUjYjCsQzVdXHGZVJkc4b33qltdzjqg0HFRkL9wAK4+f4OOiIBXSMnShnN6fLj/yEDxTmz4DAByPFcscIvmzAhBvCl4L04N92iHJbtxMimT7CHQMZ1GLNrK/11z+eUw+YG50M0/WwYU3ZkMXLvmqfZM0ljxo0cCgYBoM49fPlo4VvEEfqQes9RZj+hoc3+LXBSvw7KJljiGQiBOUNUgjxMGVL/jw75o5ayu3UXmI6zCrfEE6e6SeZw+kE/cVcaqM/jvzfJMXzB5lVuVqFeWcsr0Aus6E05FmzLv5s9oJvpHz2Y28W2UksjrqVv2YkqMir7TQ4FMRjjbbQKBgQCz3Z6KDQtfU03nBIOCN4Hcl04VK3d7dU+hGYycwhhMU/5cK4MjGLhDEBs334j0gP7WSJ3yaNcseR4/A/GnVEgCYNQBVkKMpcJn2HqcNH37R7LbI9ejMBHa5K4YdzvTFSNf6DJKuqR8DWhbT2ybRBg5/hBfZ6m4WaAzDtmAT419tQKBgHVS3Buv2TdLZA1a94AQYsmmHaAWccR8kAzRcZI5oBQTfouTfVtEQNNfAMq6Y5xO2NDuz0aY1wSeq62p1GBbzAfCZvJaKu3722H65g7pk21tn9IVzKnaWgLm6lif9NO3xX925eemZMbpmAaitD6F8eoVN85Qa8kOC1dmLwpltkn2";
+
+/// 32 byte secp256k1 private keys
+pub const ALICE_SECP256K1_BASE64_KEY: &str = "blh8GWEvewj2iYXJN/sBoWYOsoKhQM72ijvtYlFfbNA=";
+pub const BOB_SECP256K1_BASE64_KEY: &str = "fYpCKA/CiRfYNZ6b8d1E5VmbpbhizUmMqq6ejCasEdM=";
+pub const MALLORY_SECP256K1_BASE64_KEY: &str = "MOxt/XHfhAww6vY9XU5O19LDLiWoAb2jVpF2v7cKfuU=";
+pub const VICTOR_SECP256K1_BASE64_KEY: &str = "dGpq+pRoDL03yfHMwO3JjAxAqgo1NFQuYuMNgve0yH4=";
+
+/// 32 byte P-256 private keys
+pub const ALICE_P256_BASE64_KEY: &str = "6xOVAT2CDYiwSYJToRVO6AL65Lid9GWkgwR7jHrNZNk=";
+pub const BOB_P256_BASE64_KEY: &str = "lOyW73ZgECC/8cwXYI3vm2bMX7lf8/f4ipzdwC665T8=";
+pub const MALLORY_P256_BASE64_KEY: &str = "HGOxU8FCqDNaYwmZVDNfo+hF9KeU3yegd8hMcNWhK94=";
+pub const VICTOR_P256_BASE64_KEY: &str = "tdVZE6sISRThY2GdZGT0VwJirfxb4sBiOo3xEMqSww4=";
+
+impl<K> Identities<K>
+where
+    K: KeyMaterial + Clone + 'static,
+{
+    /// Public DIDs by persona name. Never includes key material, so it is
+    /// safe to publish next to fixtures.
+    pub fn dids(&self) -> BTreeMap<String, String> {
+        BTreeMap::from([
+            (String::from("alice"), self.alice_did.clone()),
+            (String::from("bob"), self.bob_did.clone()),
+            (String::from("mallory"), self.mallory_did.clone()),
+            (String::from("victor"), self.victor_did.clone()),
+        ])
+    }
+
+    /// Personas with keys decoded by `decode_key`, in alice, bob, mallory,
+    /// victor order
+    async fn from_encoded_keys(
+        encoded_keys: [&str; 4],
+        decode_key: fn(&str) -> Result<K>,
+    ) -> Result<Self> {
+        let [alice, bob, mallory, victor] = encoded_keys;
+        let alice_key = decode_key(alice).context("Could not decode alice's key")?;
+        let bob_key = decode_key(bob).context("Could not decode bob's key")?;
+        let mallory_key = decode_key(mallory).context("Could not decode mallory's key")?;
+        let victor_key = decode_key(victor).context("Could not decode victor's key")?;
+
+        Ok(Identities {
+            alice_did: alice_key.get_did().await?,
+            bob_did: bob_key.get_did().await?,
+            mallory_did: mallory_key.get_did().await?,
+            victor_did: victor_key.get_did().await?,
+
+            alice_key,
+            bob_key,
+            mallory_key,
+            victor_key,
+        })
+    }
+}
+
 impl Identities<Ed25519KeyMaterial> {
     pub async fn new() -> Self {
         let alice_key = ed25519_key_from_base64(ALICE_BASE64_KEY).unwrap();
-        let bob_key  = ed25519_key_from_base64("G4+QCX1b3a45IzQsQd4gFMMe0UB1UOx9bCsh8uOiKLER69eAvVXvc8P2yc4Iig42Bv7JD2zJxhyFALyTKBHipg==").unwrap();
+        let bob_key = ed25519_key_from_base64(BOB_BASE64_KEY).unwrap();
         let mallory_key  = ed25519_key_from_base64("LR9AL2MYkMARuvmV3MJV8sKvbSOdBtpggFCW8K62oZDR6UViSXdSV/dDcD8S9xVjS61vh62JITx7qmLgfQUSZQ==").unwrap();
+        let victor_key  = ed25519_key_from_base64("5uOkvZsU7lY2LkvNR0udyZNv/0m8rpuJ4dcVj545yXqIKxytCOkvyxNpaHvKX11khN7QDCdoPPA0jkNpnuIeJw==").unwrap();
+
+        Identities::from_keys(alice_key, bob_key, mallory_key, victor_key).await
+    }
+
+    /// Personas with freshly generated keys, for fixtures with novel
+    /// issuers. Use `new` for fixtures that are committed.
+    pub async fn random() -> Self {
+        Identities::from_keys(
+            generate_ed25519_key(),
+            generate_ed25519_key(),
+            generate_ed25519_key(),
+            generate_ed25519_key(),
+        )
+        .await
+    }
+
+    /// Personas with keys generated from `seed`, the same on every run with
+    /// the same `rand` version
+    pub async fn from_seed(seed: u64) -> Self {
+        let mut rng = StdRng::seed_from_u64(seed);
 
+        Identities::from_keys(
+            generate_ed25519_key_from_rng(&mut rng),
+            generate_ed25519_key_from_rng(&mut rng),
+            generate_ed25519_key_from_rng(&mut rng),
+            generate_ed25519_key_from_rng(&mut rng),
+        )
+        .await
+    }
+
+    async fn from_keys(
+        alice_key: Ed25519KeyMaterial,
+        bob_key: Ed25519KeyMaterial,
+        mallory_key: Ed25519KeyMaterial,
+        victor_key: Ed25519KeyMaterial,
+    ) -> Self {
         Identities {
             alice_did: alice_key.get_did().await.unwrap(),
             bob_did: bob_key.get_did().await.unwrap(),
             mallory_did: mallory_key.get_did().await.unwrap(),
+            victor_did: victor_key.get_did().await.unwrap(),
 
             alice_key,
             bob_key,
             mallory_key,
+            victor_key,
+        }
+    }
+
+    /// Default identities with alice replaced by a caller-provided key
+    pub async fn with_issuer(issuer_key: Ed25519KeyMaterial) -> Self {
+        let identities = Identities::new().await;
+
+        Identities {
+            alice_did: issuer_key.get_did().await.unwrap(),
+            alice_key: issuer_key,
+            ..identities
         }
     }
 
@@ -42,7 +174,89 @@ impl Identities<Ed25519KeyMaterial> {
             _ if did == self.alice_did => "alice".into(),
             _ if did == self.bob_did => "bob".into(),
             _ if did == self.mallory_did => "mallory".into(),
+            _ if did == self.victor_did => "victor".into(),
             _ => did,
         }
     }
 }
+
+impl Identities<RsaKeyMaterial> {
+    /// The same personas with fixed RSA keys, for RS256 signed fixtures
+    pub async fn new_rsa() -> Result<Self> {
+        Identities::from_encoded_keys(
+            [
+                ALICE_RSA_BASE64_KEY,
+                BOB_RSA_BASE64_KEY,
+                MALLORY_RSA_BASE64_KEY,
+                VICTOR_RSA_BASE64_KEY,
+            ],
+            rsa_key_from_base64,
+        )
+        .await
+        .context("Could not load the RSA identities")
+    }
+}
+
+impl Identities<Secp256k1KeyMaterial> {
+    /// The same personas with fixed secp256k1 keys, for ES256K signed fixtures
+    pub async fn new_secp256k1() -> Result<Self> {
+        Identities::from_encoded_keys(
+            [
+                ALICE_SECP256K1_BASE64_KEY,
+                BOB_SECP256K1_BASE64_KEY,
+                MALLORY_SECP256K1_BASE64_KEY,
+                VICTOR_SECP256K1_BASE64_KEY,
+            ],
+            secp256k1_key_from_base64,
+        )
+        .await
+        .context("Could not load the secp256k1 identities")
+    }
+}
+
+impl Identities<P256KeyMaterial> {
+    /// The same personas with fixed P-256 keys, for ES256 signed fixtures
+    pub async fn new_p256() -> Result<Self> {
+        Identities::from_encoded_keys(
+            [
+                ALICE_P256_BASE64_KEY,
+                BOB_P256_BASE64_KEY,
+                MALLORY_P256_BASE64_KEY,
+                VICTOR_P256_BASE64_KEY,
+            ],
+            p256_key_from_base64,
+        )
+        .await
+        .context("Could not load the P-256 identities")
+    }
+}
+
+/// The same personas under every other key type fixtures are signed with.
+/// Loading the RSA keys is slow, so these are built once and shared.
+#[derive(Clone)]
+pub struct KeyTypeIdentities {
+    pub rsa: Rc<Identities<RsaKeyMaterial>>,
+    pub secp256k1: Rc<Identities<Secp256k1KeyMaterial>>,
+    pub p256: Rc<Identities<P256KeyMaterial>>,
+}
+
+impl KeyTypeIdentities {
+    pub async fn new() -> Result<Self> {
+        Ok(KeyTypeIdentities {
+            rsa: Rc::new(Identities::new_rsa().await?),
+            secp256k1: Rc::new(Identities::new_secp256k1().await?),
+            p256: Rc::new(Identities::new_p256().await?),
+        })
+    }
+}
+
+// Not every key type implements Debug, so only the DIDs are printed
+impl fmt::Debug for KeyTypeIdentities {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KeyTypeIdentities")
+            .field("rsa", &self.rsa.dids())
+            .field("secp256k1", &self.secp256k1.dids())
+            .field("p256", &self.p256.dids())
+            .finish()
+    }
+}
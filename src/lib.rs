@@ -3,6 +3,13 @@
 #![deny(unreachable_pub, private_in_public)]
 
 //! ucan-fixture-generator
+//!
+//! Besides writing fixtures to disk, the generators can be called from a
+//! test harness. Each fixture module's `generate` takes the
+//! [`identities::Identities`] to issue with and the UCAN version to sign
+//! as, and returns its typed fixtures in memory. `verify` and `build` also
+//! take the [`identities::KeyTypeIdentities`] for their RSA, secp256k1,
+//! and P-256 fixtures. [`generators::all_fixtures`] returns every fixture.
 
 pub mod capabilities;
 pub mod crypto;
@@ -1,72 +1,600 @@
 //! ucan-fixture-generator
 
+use anyhow::{ensure, Context, Result};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use serde::Serialize;
 use serde_json::Value;
-use std::fs;
-use ucan_fixture_generator::generators::{build, refute, to_cid, verify};
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+    process,
+    rc::Rc,
+};
+use tokio::runtime;
+use ucan_fixture_generator::{
+    capabilities::AbilityHierarchy,
+    crypto::ed25519_key_from_base64,
+    generators::{
+        all_fixtures, errors, imported, redact::redact_signatures, refute, schema,
+        validate::validate, verify, AnyFixture, UCAN_VERSIONS,
+    },
+    identities::{Identities, KeyTypeIdentities},
+};
+use ucan_key_support::ed25519::Ed25519KeyMaterial;
+
+#[derive(Debug, Parser)]
+#[command(author, version, about)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// Run without a tokio IO driver, so that tokio network I/O panics
+    #[arg(long, global = true)]
+    assert_no_network: bool,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Generate fixtures (the default when no subcommand is given)
+    Generate(GenerateArgs),
+    /// Exit with an error if the fixtures on disk are out of date
+    Check(OutputArgs),
+    /// List fixtures that were added, removed, or changed since the last generate
+    Diff(OutputArgs),
+    /// Write only the JSON Schema of each task file, without generating fixtures
+    Schema(SchemaArgs),
+}
+
+#[derive(Debug, Args)]
+struct SchemaArgs {
+    /// Directory the `schema` directory of each UCAN version is written to
+    #[arg(long, value_name = "PATH", default_value = "fixtures")]
+    out_dir: PathBuf,
+}
+
+#[derive(Debug, Args)]
+struct OutputArgs {
+    /// Keep generator order instead of sorting fixtures by name
+    #[arg(long)]
+    no_sort: bool,
+
+    /// Directory of imported regression fixtures to fold into the output
+    #[arg(long, value_name = "DIR", default_value = "regressions")]
+    imports: PathBuf,
+
+    /// Directory fixtures are written to and compared against
+    #[arg(long, value_name = "PATH", default_value = "fixtures")]
+    out_dir: PathBuf,
+
+    /// How every fixture is written in one file: a JSON array in
+    /// `all.json`, or a fixture per line in `all.ndjson`. Either file is
+    /// written once every fixture for its version has been generated.
+    #[arg(long, value_enum, default_value_t = Format::Json)]
+    format: Format,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Format {
+    Json,
+    Ndjson,
+}
+
+impl Default for OutputArgs {
+    fn default() -> Self {
+        OutputArgs {
+            no_sort: false,
+            imports: PathBuf::from("regressions"),
+            out_dir: PathBuf::from("fixtures"),
+            format: Format::Json,
+        }
+    }
+}
+
+#[derive(Debug, Default, Args)]
+struct GenerateArgs {
+    #[command(flatten)]
+    output: OutputArgs,
+
+    /// JSON file of ability implications to generate delegation fixtures for
+    #[arg(long, value_name = "PATH")]
+    hierarchy: Option<PathBuf>,
+
+    /// File holding a base64 encoded Ed25519 key to issue fixtures with
+    #[arg(long, value_name = "PATH")]
+    issuer_key_file: Option<PathBuf>,
+
+    /// Also write fixtures issued with keys generated from this seed to
+    /// `seed`, with build fixtures skipped
+    #[arg(long, value_name = "SEED")]
+    seed: Option<u64>,
+
+    /// Print fixture counts per task and category after generating
+    #[arg(long)]
+    stats: bool,
+
+    /// Also write copies of each fixture file with placeholder signatures,
+    /// for documentation, under a `redacted` directory per UCAN version
+    #[arg(long)]
+    redact_signatures: bool,
+}
+
+/// The file each task's fixtures are written to, in the order they are
+/// folded into `all.json`
+const TASK_FILES: &[(&str, &str)] = &[
+    ("verify", "verify.json"),
+    ("refute", "refute.json"),
+    ("prove", "prove.json"),
+    ("disprove", "disprove.json"),
+    ("build", "build.json"),
+    ("toCID", "cid.json"),
+];
+
+/// A `manifest.json` entry, naming a fixture and the task file it is in
+#[derive(Debug, Serialize)]
+struct ManifestEntry {
+    task: &'static str,
+    name: String,
+    file: &'static str,
+}
+
+/// Fixture file contents keyed by path relative to `fixtures`, starting
+/// with the UCAN version
+type FixtureFiles = BTreeMap<String, String>;
 
 /// Main entry point
-#[tokio::main]
-async fn main() {
-    const UCV: &str = "0.10.0";
+fn main() {
+    let cli = Cli::parse();
+
+    // Tokio network I/O panics on a runtime built without its IO driver,
+    // so leaving it out catches DID resolution going online through tokio.
+    // Blocking std network calls are not caught.
+    let mut builder = runtime::Builder::new_multi_thread();
+    builder.enable_time();
+    if !cli.assert_no_network {
+        builder.enable_all();
+    }
+    let runtime = builder.build().expect("Could not start runtime");
+
+    runtime.block_on(async {
+        match cli
+            .command
+            .unwrap_or_else(|| Command::Generate(GenerateArgs::default()))
+        {
+            Command::Generate(args) => generate(args).await,
+            Command::Check(args) => check(args).await,
+            Command::Diff(args) => diff(args).await,
+            Command::Schema(args) => write_schemas(args),
+        }
+    })
+}
+
+async fn generate(args: GenerateArgs) {
+    let sort = !args.output.no_sort;
+    let identities = Rc::new(Identities::new().await);
+    let key_types = KeyTypeIdentities::new()
+        .await
+        .unwrap_or_else(exit_with_error);
+    let mut files = task_files(&args.output, identities.clone(), &key_types)
+        .await
+        .unwrap_or_else(exit_with_error);
+
+    // Fixtures for a caller-provided ability hierarchy
+    if let Some(path) = args.hierarchy {
+        files.extend(
+            hierarchy_files(&path, identities.clone(), sort)
+                .await
+                .unwrap_or_else(exit_with_error),
+        );
+    }
+
+    // Fixtures issued by a caller-provided key. The key is only read at
+    // generation time and never written, so build fixtures (which embed
+    // the issuer private key) are skipped.
+    if let Some(path) = args.issuer_key_file {
+        let encoded_key = fs::read_to_string(&path).expect("Could not read issuer key file");
+        let issuer_key = ed25519_key_from_base64(encoded_key.trim()).unwrap_or_else(|err| {
+            exit_with_error(err.context(format!("Could not decode issuer key {}", path.display())))
+        });
+        let issuer_identities = Identities::with_issuer(issuer_key).await;
+
+        files.extend(
+            reissued_files("issuer", issuer_identities, &key_types, sort)
+                .await
+                .unwrap_or_else(exit_with_error),
+        );
+    }
+
+    // Fixtures issued by keys generated from a seed. These go to their own
+    // directory, so the default fixtures, build included, stay as they are.
+    if let Some(seed) = args.seed {
+        files.extend(
+            reissued_files("seed", Identities::from_seed(seed).await, &key_types, sort)
+                .await
+                .unwrap_or_else(exit_with_error),
+        );
+    }
+
+    if args.stats {
+        print_stats(&files);
+    }
+
+    if args.redact_signatures {
+        files.extend(redacted_files(&files));
+    }
+
+    write_files(&args.output.out_dir, files);
+}
+
+fn write_schemas(args: SchemaArgs) {
+    let files = UCAN_VERSIONS
+        .iter()
+        .flat_map(|version| schema_files(version))
+        .collect();
+
+    write_files(&args.out_dir, files);
+}
+
+fn write_files(out_dir: &Path, files: FixtureFiles) {
+    for (file, contents) in files {
+        let path = out_dir.join(file);
+
+        fs::create_dir_all(path.parent().unwrap()).expect("Could not create fixtures directory");
+        fs::write(&path, contents).unwrap_or_else(|err| println!("{:?}", err));
+    }
+}
+
+/// Redacted copies of every file of fixtures, written to `redacted` beside
+/// the original. Files that are not fixture arrays are skipped.
+fn redacted_files(files: &FixtureFiles) -> FixtureFiles {
+    let mut redacted = FixtureFiles::new();
+
+    for (file, contents) in files {
+        let fixtures: Option<Vec<Value>> = serde_json::from_str(contents).ok();
+
+        if let (Some((version, name)), Some(mut fixtures)) = (file.split_once('/'), fixtures) {
+            if !fixtures
+                .iter()
+                .all(|fixture| fixture.get("inputs").is_some())
+            {
+                continue;
+            }
+
+            fixtures.iter_mut().for_each(redact_signatures);
+
+            redacted.insert(
+                format!("{}/redacted/{}", version, name),
+                serde_json::to_string_pretty(&fixtures).unwrap(),
+            );
+        }
+    }
+
+    redacted
+}
+
+/// Print a line per task like "verify: 15 (time:5, capability:3, ...)"
+fn print_stats(files: &FixtureFiles) {
+    for version in UCAN_VERSIONS {
+        println!("{}", version);
+        print_version_stats(files, version);
+    }
+}
+
+fn print_version_stats(files: &FixtureFiles, version: &str) {
+    for (task, file) in TASK_FILES {
+        let fixtures: Vec<Value> = files
+            .get(&format!("{}/{}", version, file))
+            .and_then(|contents| serde_json::from_str(contents).ok())
+            .unwrap_or_default();
+
+        let mut categories: BTreeMap<String, usize> = BTreeMap::new();
+        for fixture in &fixtures {
+            let category = fixture
+                .get("category")
+                .and_then(Value::as_str)
+                .unwrap_or("uncategorized");
+            *categories.entry(category.to_string()).or_default() += 1;
+        }
+
+        let counts: Vec<String> = categories
+            .iter()
+            .map(|(category, count)| format!("{}:{}", category, count))
+            .collect();
+
+        println!("{}: {} ({})", task, fixtures.len(), counts.join(", "));
+    }
+}
+
+async fn check(args: OutputArgs) {
+    let key_types = KeyTypeIdentities::new()
+        .await
+        .unwrap_or_else(exit_with_error);
+    let files = task_files(&args, Rc::new(Identities::new().await), &key_types)
+        .await
+        .unwrap_or_else(exit_with_error);
+
+    let stale: Vec<String> = files
+        .into_iter()
+        .filter(|(file, contents)| {
+            fs::read_to_string(args.out_dir.join(file)).ok() != Some(contents.clone())
+        })
+        .map(|(file, _)| file)
+        .collect();
+
+    if !stale.is_empty() {
+        for file in stale {
+            println!("{} is out of date", args.out_dir.join(file).display());
+        }
+
+        process::exit(1);
+    }
+}
+
+async fn diff(args: OutputArgs) {
+    let key_types = KeyTypeIdentities::new()
+        .await
+        .unwrap_or_else(exit_with_error);
+    let files = task_files(&args, Rc::new(Identities::new().await), &key_types)
+        .await
+        .unwrap_or_else(exit_with_error);
+
+    for (file, contents) in files {
+        let existing = fs::read_to_string(args.out_dir.join(&file)).unwrap_or_default();
+
+        if existing == contents {
+            continue;
+        }
+
+        println!("{}", args.out_dir.join(&file).display());
+
+        let before = fixtures_by_name(&file, &existing);
+        let after = fixtures_by_name(&file, &contents);
+
+        match (before, after) {
+            (Some(before), Some(after)) => {
+                for (name, fixture) in &after {
+                    match before.get(name) {
+                        None => println!("  + {}", name),
+                        Some(previous) if previous != fixture => println!("  ~ {}", name),
+                        Some(_) => {}
+                    }
+                }
+
+                for name in before.keys().filter(|name| !after.contains_key(*name)) {
+                    println!("  - {}", name);
+                }
+            }
+            _ => println!("  ~ (whole file)"),
+        }
+    }
+}
+
+/// Generate every task file written on each run, for every UCAN version,
+/// with the one set of `identities`
+async fn task_files(
+    args: &OutputArgs,
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    key_types: &KeyTypeIdentities,
+) -> Result<FixtureFiles> {
+    let mut files = FixtureFiles::new();
+
+    for version in UCAN_VERSIONS {
+        files.extend(version_files(args, identities.clone(), key_types, version).await?);
+    }
+
+    Ok(files)
+}
 
-    fs::create_dir_all(format!("fixtures/{}", UCV)).expect("Could not create fixtures directory");
+async fn version_files(
+    args: &OutputArgs,
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    key_types: &KeyTypeIdentities,
+    version: &str,
+) -> Result<FixtureFiles> {
+    let sort = !args.no_sort;
+    let imported = imported::load_dir(&args.imports).context("Could not import fixtures")?;
+
+    let mut fixtures = all_fixtures(identities.clone(), key_types, version).await?;
+    validate(&fixtures).context("Generated a token that does not decode")?;
+
+    // Every generated token must be signed with the version of the
+    // directory it is written to
+    for fixture in &fixtures {
+        if let AnyFixture::Verify(fixture) = fixture {
+            let value = serde_json::to_value(fixture)?;
+            ensure!(
+                value["assertions"]["payload"]["ucv"] == version,
+                "{} is not a {} fixture",
+                fixture.name(),
+                version
+            );
+        }
+    }
+
+    fixtures.extend(imported.verify.into_iter().map(AnyFixture::Verify));
+    fixtures.extend(imported.refute.into_iter().map(AnyFixture::Refute));
+
+    let mut files = FixtureFiles::new();
 
     // Fixtures by task
-    let verify_fixtures = verify::generate().await.unwrap();
-    let refute_fixtures = refute::generate().await.unwrap();
-    let build_fixtures = build::generate().await.unwrap();
-    let to_cid_fixtures = to_cid::generate().await.unwrap();
-
-    fs::write(
-        format!("fixtures/{}/verify.json", UCV),
-        serde_json::to_string(&verify_fixtures).unwrap(),
-    )
-    .unwrap_or_else(|err| println!("{:?}", err));
-
-    fs::write(
-        format!("fixtures/{}/refute.json", UCV),
-        serde_json::to_string(&refute_fixtures).unwrap(),
-    )
-    .unwrap_or_else(|err| println!("{:?}", err));
-
-    fs::write(
-        format!("fixtures/{}/build.json", UCV),
-        serde_json::to_string(&build_fixtures).unwrap(),
-    )
-    .unwrap_or_else(|err| println!("{:?}", err));
-
-    fs::write(
-        format!("fixtures/{}/cid.json", UCV),
-        serde_json::to_string(&to_cid_fixtures).unwrap(),
-    )
-    .unwrap_or_else(|err| println!("{:?}", err));
+    let mut all: Vec<AnyFixture> = vec![];
+    let mut manifest: Vec<ManifestEntry> = vec![];
+    for (file, fixtures) in task_groups(&fixtures, sort) {
+        files.insert(
+            format!("{}/{}", version, file),
+            serde_json::to_string_pretty(&fixtures).unwrap(),
+        );
+        manifest.extend(fixtures.iter().map(|fixture| ManifestEntry {
+            task: fixture.task(),
+            name: fixture.name().to_string(),
+            file,
+        }));
+        all.extend(fixtures);
+    }
+
+    files.insert(
+        format!("{}/error_codes.json", version),
+        serde_json::to_string_pretty(&errors::error_codes()).unwrap(),
+    );
+    files.insert(
+        format!("{}/identities.json", version),
+        serde_json::to_string_pretty(&identities.dids()).unwrap(),
+    );
+
+    files.extend(schema_files(version));
 
     // All fixtures
-    let mut all_fixtures: Vec<Value> = vec![];
+    match args.format {
+        Format::Json => files.insert(
+            format!("{}/all.json", version),
+            serde_json::to_string_pretty(&all).unwrap(),
+        ),
+        Format::Ndjson => files.insert(format!("{}/all.ndjson", version), ndjson(&all)),
+    };
+    files.insert(
+        format!("{}/manifest.json", version),
+        serde_json::to_string_pretty(&manifest).unwrap(),
+    );
 
-    for fixture in verify_fixtures {
-        let value = serde_json::to_value(&fixture).unwrap();
-        all_fixtures.push(value);
-    }
+    Ok(files)
+}
+
+/// Print an error with its causes and exit
+fn exit_with_error(err: anyhow::Error) -> ! {
+    eprintln!("{:#}", err);
+    process::exit(1)
+}
+
+/// One compact JSON object per line, each ending in a newline
+fn ndjson(fixtures: &[AnyFixture]) -> String {
+    fixtures
+        .iter()
+        .map(|fixture| serde_json::to_string(fixture).unwrap() + "\n")
+        .collect()
+}
+
+/// Delegation fixtures for the ability hierarchy read from `path`, written
+/// to `hierarchy` under each version
+async fn hierarchy_files(
+    path: &Path,
+    identities: Rc<Identities<Ed25519KeyMaterial>>,
+    sort: bool,
+) -> Result<FixtureFiles> {
+    let hierarchy = AbilityHierarchy::from_file(path)
+        .with_context(|| format!("Could not load ability hierarchy {}", path.display()))?;
+    let mut files = FixtureFiles::new();
 
-    for fixture in refute_fixtures {
-        let value = serde_json::to_value(&fixture).unwrap();
-        all_fixtures.push(value);
+    for version in UCAN_VERSIONS {
+        let verify_fixtures = sorted(
+            verify::generate_for_hierarchy(identities.clone(), &hierarchy, version).await?,
+            sort,
+            verify::VerifyFixture::name,
+        );
+        let refute_fixtures = sorted(
+            refute::generate_for_hierarchy(identities.clone(), &hierarchy, version).await?,
+            sort,
+            refute::RefuteFixture::name,
+        );
+
+        files.insert(
+            format!("{}/hierarchy/verify.json", version),
+            serde_json::to_string_pretty(&verify_fixtures)?,
+        );
+        files.insert(
+            format!("{}/hierarchy/refute.json", version),
+            serde_json::to_string_pretty(&refute_fixtures)?,
+        );
     }
 
-    for fixture in build_fixtures {
-        let value = serde_json::to_value(&fixture).unwrap();
-        all_fixtures.push(value);
+    Ok(files)
+}
+
+/// Every task file issued by `identities` rather than the defaults, written
+/// to `dir` under each version with an `identities.json` sidecar of their
+/// DIDs. Their keys are not the defaults, so build fixtures are skipped.
+async fn reissued_files(
+    dir: &str,
+    identities: Identities<Ed25519KeyMaterial>,
+    key_types: &KeyTypeIdentities,
+    sort: bool,
+) -> Result<FixtureFiles> {
+    let identities = Rc::new(identities);
+    let mut files = FixtureFiles::new();
+
+    for version in UCAN_VERSIONS {
+        let fixtures = all_fixtures(identities.clone(), key_types, version).await?;
+        validate(&fixtures).context("Generated a token that does not decode")?;
+
+        for (file, fixtures) in task_groups(&fixtures, sort) {
+            files.insert(
+                format!("{}/{}/{}", version, dir, file),
+                serde_json::to_string_pretty(&fixtures)?,
+            );
+        }
+        files.insert(
+            format!("{}/{}/identities.json", version, dir),
+            serde_json::to_string_pretty(&identities.dids())?,
+        );
     }
 
-    for fixture in to_cid_fixtures {
-        let value = serde_json::to_value(&fixture).unwrap();
-        all_fixtures.push(value);
+    Ok(files)
+}
+
+/// The JSON Schema of each task file, under `schema` for `version`
+fn schema_files(version: &str) -> FixtureFiles {
+    schema::schemas()
+        .into_iter()
+        .map(|(file, schema)| {
+            (
+                format!("{}/schema/{}", version, file),
+                serde_json::to_string_pretty(&schema).unwrap(),
+            )
+        })
+        .collect()
+}
+
+/// Split fixtures into the file written for each task, in `TASK_FILES`
+/// order. Tasks without fixtures get no file.
+fn task_groups(fixtures: &[AnyFixture], sort: bool) -> Vec<(&'static str, Vec<AnyFixture>)> {
+    TASK_FILES
+        .iter()
+        .map(|(task, file)| {
+            let group: Vec<AnyFixture> = fixtures
+                .iter()
+                .filter(|fixture| fixture.task() == *task)
+                .cloned()
+                .collect();
+
+            (*file, sorted(group, sort, AnyFixture::name))
+        })
+        .filter(|(_, group)| !group.is_empty())
+        .collect()
+}
+
+/// Sort by name so reordering generators does not reorder output
+fn sorted<T>(mut fixtures: Vec<T>, sort: bool, name: impl Fn(&T) -> &str) -> Vec<T> {
+    if sort {
+        fixtures.sort_by(|a, b| name(a).cmp(name(b)));
     }
 
-    fs::write(
-        format!("fixtures/{}/all.json", UCV),
-        serde_json::to_string(&all_fixtures).unwrap(),
-    )
-    .unwrap_or_else(|err| println!("{:?}", err))
+    fixtures
+}
+
+fn fixtures_by_name(file: &str, contents: &str) -> Option<BTreeMap<String, Value>> {
+    let fixtures: Vec<Value> = if file.ends_with(".ndjson") {
+        contents
+            .lines()
+            .map(|line| serde_json::from_str(line).ok())
+            .collect::<Option<_>>()?
+    } else {
+        serde_json::from_str(contents).ok()?
+    };
+
+    fixtures
+        .into_iter()
+        .map(|fixture| {
+            let name = fixture.get("name")?.as_str()?.to_string();
+            Some((name, fixture))
+        })
+        .collect()
 }
@@ -0,0 +1,361 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use cid::{
+    multihash::{Code, MultihashDigest},
+    Cid,
+};
+use libipld_core::{
+    codec::{Codec, Decode, Encode},
+    raw::RawCodec,
+};
+use serde_json::Value;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Debug,
+    io::Cursor,
+    rc::Rc,
+    str::FromStr,
+    time::{SystemTime, UNIX_EPOCH},
+};
+use ucan::{
+    capability::{Ability, Capability, CapabilitySemantics, Scope},
+    chain::ProofChain,
+    crypto::did::DidParser,
+    store::{UcanJwtStore, UcanStore},
+    Ucan,
+};
+use ucan_fixture_generator::{
+    capabilities::{EmailSemantics, HttpSemantics, SegmentSemantics, WnfsSemantics},
+    crypto::SUPPORTED_KEYS,
+    generators::{all_fixtures, UCAN_VERSIONS},
+    identities::{Identities, KeyTypeIdentities},
+};
+
+/// The parts of validation the `ucan` crate can check on a single token:
+/// its signature, made with the iss key under the header alg, and its time
+/// bounds at `time`, widened by `skew`. An iss listed in `resolved` is
+/// replaced with the did:key it resolves to.
+async fn check_token(
+    token: &str,
+    time: u64,
+    skew: u64,
+    resolved: &Value,
+) -> std::result::Result<(), String> {
+    let mut did_parser = DidParser::new(SUPPORTED_KEYS);
+    let ucan = Ucan::from_str(token).map_err(|err| format!("malformed: {}", err))?;
+
+    let issuer = resolved[ucan.issuer()].as_str().unwrap_or(ucan.issuer());
+    let key = did_parser
+        .parse(issuer)
+        .map_err(|err| format!("invalidSignature: {}", err))?;
+    if key.get_jwt_algorithm_name() != ucan.algorithm() {
+        return Err(String::from("invalidSignature: alg does not match iss key"));
+    }
+    key.verify(ucan.signed_data(), ucan.signature())
+        .await
+        .map_err(|err| format!("invalidSignature: {}", err))?;
+
+    if matches!(ucan.expires_at(), Some(exp) if time > *exp + skew) {
+        return Err(String::from("expired"));
+    }
+    if matches!(ucan.not_before(), Some(nbf) if time + skew < *nbf) {
+        return Err(String::from("notReady"));
+    }
+
+    Ok(())
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+}
+
+fn tokens(fixture: &Value) -> Vec<&str> {
+    let inputs = &fixture["inputs"];
+    let proofs = inputs["proofs"].as_array().unwrap();
+    let encodings = fixture["encodings"].as_array().into_iter().flatten();
+
+    std::iter::once(&inputs["token"])
+        .chain(proofs)
+        .chain(encodings.map(|encoding| &encoding["token"]))
+        .map(|token| token.as_str().unwrap())
+        .collect()
+}
+
+/// Proofs keyed by the sha2-256 CID fixtures cite them by in `prf`. The
+/// `ucan` crate's `MemoryStore` keys tokens by a BLAKE3 CID instead.
+#[derive(Default)]
+struct ProofStore(BTreeMap<Cid, Vec<u8>>);
+
+#[async_trait]
+impl UcanStore<RawCodec> for ProofStore {
+    async fn read<T: Decode<RawCodec>>(&self, cid: &Cid) -> Result<Option<T>> {
+        self.0
+            .get(cid)
+            .map(|bytes| T::decode(RawCodec, &mut Cursor::new(bytes)))
+            .transpose()
+    }
+
+    async fn write<T: Encode<RawCodec> + Send + Debug>(&mut self, token: T) -> Result<Cid> {
+        let bytes = RawCodec.encode(&token)?;
+        let cid = Cid::new_v1(RawCodec.into(), Code::Sha2_256.digest(&bytes));
+        self.0.insert(cid, bytes);
+
+        Ok(cid)
+    }
+}
+
+/// The `ucan` crate's own validation of `token` and the `proofs` it rests
+/// on, at `time`
+async fn proof_chain(token: &str, proofs: &[&str], time: u64) -> Result<ProofChain> {
+    let mut store = ProofStore::default();
+    for proof in proofs {
+        store.write_token(proof).await?;
+    }
+    let mut did_parser = DidParser::new(SUPPORTED_KEYS);
+
+    ProofChain::from_token_string(token, Some(time), &mut did_parser, &store).await
+}
+
+/// The `ucan` crate does not tolerate clock skew, so a fixture that does
+/// is checked at `time` and at each end of its skew window
+fn check_times(time: u64, skew: u64) -> Vec<u64> {
+    if skew == 0 {
+        vec![time]
+    } else {
+        vec![time - skew, time, time + skew]
+    }
+}
+
+/// The issuers at the root of every branch of the chain
+fn root_issuers(chain: &ProofChain) -> BTreeSet<String> {
+    if chain.proofs().is_empty() {
+        BTreeSet::from([chain.ucan().issuer().to_string()])
+    } else {
+        chain.proofs().iter().flat_map(root_issuers).collect()
+    }
+}
+
+/// Whether `chain` grants `capability` originating from one of `issuers`,
+/// or `None` if no semantics the fixtures use can parse it
+fn grants(chain: &ProofChain, capability: &Capability, issuers: &BTreeSet<String>) -> Option<bool> {
+    grants_under(chain, &EmailSemantics {}, capability, issuers)
+        .or_else(|| grants_under(chain, &WnfsSemantics {}, capability, issuers))
+        .or_else(|| grants_under(chain, &HttpSemantics {}, capability, issuers))
+        .or_else(|| grants_under(chain, &SegmentSemantics {}, capability, issuers))
+}
+
+fn grants_under<Semantics, S, A>(
+    chain: &ProofChain,
+    semantics: &Semantics,
+    capability: &Capability,
+    issuers: &BTreeSet<String>,
+) -> Option<bool>
+where
+    Semantics: CapabilitySemantics<S, A>,
+    S: Scope,
+    A: Ability,
+{
+    let claimed = semantics.parse_capability(capability)?;
+
+    Some(
+        chain.reduce_capabilities(semantics).iter().any(|info| {
+            !info.originators.is_disjoint(issuers) && info.capability.enables(&claimed)
+        }),
+    )
+}
+
+/// The capabilities a token claims, from its decoded `cap`
+fn claimed_capabilities(fixture: &Value) -> Vec<Capability> {
+    let cap = &fixture["assertions"]["decoded"]["payload"]["cap"];
+
+    cap.as_object()
+        .into_iter()
+        .flatten()
+        .flat_map(|(resource, abilities)| {
+            abilities
+                .as_object()
+                .into_iter()
+                .flatten()
+                .flat_map(move |(ability, caveats)| {
+                    caveats
+                        .as_array()
+                        .into_iter()
+                        .flatten()
+                        .map(move |caveat| Capability {
+                            resource: resource.clone(),
+                            ability: ability.clone(),
+                            caveat: caveat.clone(),
+                        })
+                })
+        })
+        .collect()
+}
+
+/// Whether the chain for `fixture` is rejected at `time`, either while it
+/// is built or because a capability the token claims does not trace back
+/// to a root issuer
+async fn delegation_rejected(fixture: &Value, token: &str, proofs: &[&str], time: u64) -> bool {
+    let chain = match proof_chain(token, proofs, time).await {
+        Ok(chain) => chain,
+        Err(_) => return true,
+    };
+    let roots = root_issuers(&chain);
+
+    claimed_capabilities(fixture)
+        .iter()
+        .any(|capability| grants(&chain, capability, &roots) == Some(false))
+}
+
+// Decode all.json as a harness would, and check every fixture against the
+// `ucan` crate: its signature and time checks on each token, then a
+// `ProofChain` over the token and its proofs. Fixtures issued by DIDs the
+// crate cannot resolve are only checked token by token.
+#[tokio::test]
+async fn all_fixtures_agree_with_ucan_crate() {
+    let fixtures = all_fixtures(
+        Rc::new(Identities::new().await),
+        &KeyTypeIdentities::new().await.unwrap(),
+        UCAN_VERSIONS[0],
+    )
+    .await
+    .unwrap();
+    let all_json = serde_json::to_string(&fixtures).unwrap();
+    let fixtures: Vec<Value> = serde_json::from_str(&all_json).unwrap();
+
+    for fixture in &fixtures {
+        let name = fixture["name"].as_str().unwrap();
+        let time = fixture["time"].as_u64().unwrap_or_else(now);
+        let skew = fixture["clock_skew_seconds"].as_u64().unwrap_or(0);
+        let resolved = &fixture["resolved_dids"];
+        let resolvable = resolved.is_null();
+        let proofs: Vec<&str> = fixture["inputs"]["proofs"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .map(|proof| proof.as_str().unwrap())
+            .collect();
+
+        match fixture["task"].as_str().unwrap() {
+            "verify" => {
+                for token in tokens(fixture) {
+                    check_token(token, time, skew, resolved)
+                        .await
+                        .unwrap_or_else(|err| panic!("{}: {}", name, err));
+                }
+
+                if resolvable {
+                    let encodings = fixture["encodings"].as_array().into_iter().flatten();
+                    let leaves = std::iter::once(&fixture["inputs"]["token"])
+                        .chain(encodings.map(|encoding| &encoding["token"]));
+
+                    for token in leaves.map(|token| token.as_str().unwrap()) {
+                        let mut errors = vec![];
+                        for time in check_times(time, skew) {
+                            match proof_chain(token, &proofs, time).await {
+                                Ok(_) => {
+                                    errors.clear();
+                                    break;
+                                }
+                                Err(err) => errors.push(err),
+                            }
+                        }
+
+                        assert!(errors.is_empty(), "{}: {:?}", name, errors);
+                    }
+                }
+            }
+
+            "build" => {
+                let token = fixture["outputs"]["token"].as_str().unwrap();
+                check_token(token, time, skew, resolved)
+                    .await
+                    .unwrap_or_else(|err| panic!("{}: {}", name, err));
+                proof_chain(token, &proofs, time)
+                    .await
+                    .unwrap_or_else(|err| panic!("{}: {}", name, err));
+            }
+
+            "prove" | "disprove" => {
+                let token = fixture["inputs"]["token"].as_str().unwrap();
+                let issuers = BTreeSet::from([fixture["issuer"].as_str().unwrap().to_string()]);
+                let capabilities: Vec<Capability> =
+                    serde_json::from_value(fixture["capabilities"].clone()).unwrap();
+                let chain = proof_chain(token, &proofs, time).await;
+
+                let proven = match &chain {
+                    Ok(chain) => capabilities.iter().all(|capability| {
+                        grants(chain, capability, &issuers).unwrap_or_else(|| {
+                            panic!("{}: no semantics parse {:?}", name, capability)
+                        })
+                    }),
+                    Err(_) => false,
+                };
+
+                if fixture["task"] == "prove" {
+                    assert!(proven, "{} is not proven: {:?}", name, chain.err());
+                } else {
+                    assert!(!proven, "{} is proven", name);
+                }
+            }
+
+            "refute" => {
+                let token = fixture["inputs"]["token"].as_str().unwrap();
+                let errors: Vec<&str> = fixture["errors"]
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .map(|error| error.as_str().unwrap())
+                    .collect();
+
+                if let ["expired" | "notReady" | "invalidSignature"] = errors[..] {
+                    let err = check_token(token, time, skew, resolved)
+                        .await
+                        .expect_err(&format!("{} was not rejected", name));
+
+                    assert!(err.starts_with(errors[0]), "{}: {}", name, err);
+                }
+
+                if resolvable && errors.contains(&"invalidDelegation") {
+                    for time in check_times(time, skew) {
+                        assert!(
+                            delegation_rejected(fixture, token, &proofs, time).await,
+                            "{} was not rejected at {}",
+                            name,
+                            time
+                        );
+                    }
+                }
+            }
+
+            _ => {}
+        }
+    }
+}
+
+// A version not yet in UCAN_VERSIONS must still be emitted as every
+// verify token's ucv, so adding it only takes a new list entry
+#[tokio::test]
+async fn verify_tokens_are_signed_with_the_requested_version() {
+    let fixtures = all_fixtures(
+        Rc::new(Identities::new().await),
+        &KeyTypeIdentities::new().await.unwrap(),
+        "0.9.1",
+    )
+    .await
+    .unwrap();
+
+    for fixture in fixtures {
+        let value = serde_json::to_value(&fixture).unwrap();
+        if value["task"] != "verify" {
+            continue;
+        }
+
+        for token in tokens(&value) {
+            let ucan = Ucan::from_str(token).unwrap();
+            assert_eq!(ucan.version(), "0.9.1", "{}", fixture.name());
+        }
+    }
+}
@@ -0,0 +1,144 @@
+use serde_json::{json, Value};
+use std::rc::Rc;
+use ucan_fixture_generator::{
+    generators::{mutate::raw_part, refute, verify, UCAN_VERSIONS},
+    identities::{Identities, KeyTypeIdentities},
+};
+
+#[tokio::test]
+async fn decoded_matches_assertions() {
+    for fixture in verify::generate(
+        Rc::new(Identities::new().await),
+        &KeyTypeIdentities::new().await.unwrap(),
+        UCAN_VERSIONS[0],
+    )
+    .await
+    .unwrap()
+    {
+        let value = serde_json::to_value(&fixture).unwrap();
+        let assertions = &value["assertions"];
+        let decoded = &assertions["decoded"];
+
+        assert_eq!(decoded["header"]["alg"], assertions["header"]["alg"]);
+        assert_eq!(decoded["payload"]["iss"], assertions["payload"]["iss"]);
+        assert_eq!(decoded["payload"]["aud"], assertions["payload"]["aud"]);
+    }
+}
+
+#[tokio::test]
+async fn never_expires_asserts_null_exp() {
+    let fixture = verify::generate(
+        Rc::new(Identities::new().await),
+        &KeyTypeIdentities::new().await.unwrap(),
+        UCAN_VERSIONS[0],
+    )
+    .await
+    .unwrap()
+    .into_iter()
+    .find(|fixture| fixture.name() == "UCAN with a null exp never expires")
+    .unwrap();
+    let value = serde_json::to_value(&fixture).unwrap();
+    let payload = value["assertions"]["payload"].as_object().unwrap();
+
+    assert_eq!(payload.get("exp"), Some(&json!(null)));
+    assert_eq!(
+        value["assertions"]["decoded"]["payload"]["exp"],
+        json!(null)
+    );
+}
+
+// Mutations happen after the assertions are built, so decoded must follow
+// the token as sent rather than the token first signed
+#[tokio::test]
+async fn decoded_follows_mutated_token() {
+    let fixture = refute::generate(Rc::new(Identities::new().await), UCAN_VERSIONS[0])
+        .await
+        .unwrap()
+        .into_iter()
+        .find(|fixture| fixture.name() == "UCAN payload prf field is not an array of CIDs")
+        .unwrap();
+    let value = serde_json::to_value(&fixture).unwrap();
+
+    assert_eq!(
+        value["assertions"]["decoded"]["payload"]["prf"],
+        json!(["we", "prove", "nothing"])
+    );
+}
+
+#[tokio::test]
+async fn facts_keep_their_types() {
+    let fixture = verify::generate(
+        Rc::new(Identities::new().await),
+        &KeyTypeIdentities::new().await.unwrap(),
+        UCAN_VERSIONS[0],
+    )
+    .await
+    .unwrap()
+    .into_iter()
+    .find(|fixture| fixture.name() == "UCAN has facts of every JSON type")
+    .unwrap();
+    let value = serde_json::to_value(&fixture).unwrap();
+
+    let expected = json!({
+        "challenge": "abcdef",
+        "count": 42,
+        "profile": { "name": "alice", "address": { "city": "Lisbon" } },
+        "ratio": 0.5,
+        "revoked": null,
+        "tags": ["email", 7, false],
+        "verified": true
+    });
+    assert_eq!(value["assertions"]["payload"]["fct"], expected);
+    assert_eq!(value["assertions"]["decoded"]["payload"]["fct"], expected);
+}
+
+// Each hop narrows the path, so the leaf claims only the narrowest of the
+// resources its proofs delegate
+#[tokio::test]
+async fn three_hop_chain_narrows_every_hop() {
+    let fixture = verify::generate(
+        Rc::new(Identities::new().await),
+        &KeyTypeIdentities::new().await.unwrap(),
+        UCAN_VERSIONS[0],
+    )
+    .await
+    .unwrap()
+    .into_iter()
+    .find(|fixture| fixture.name() == "UCAN has a three-hop delegation chain")
+    .unwrap();
+
+    let claims: Vec<(String, Vec<String>)> = fixture
+        .inputs
+        .proofs
+        .iter()
+        .chain(std::iter::once(&fixture.inputs.token))
+        .map(|token| {
+            let payload: Value =
+                serde_json::from_str(&raw_part(token, "payload").unwrap()).unwrap();
+            let (resource, abilities) = payload["cap"].as_object().unwrap().iter().next().unwrap();
+
+            (
+                resource.clone(),
+                abilities.as_object().unwrap().keys().cloned().collect(),
+            )
+        })
+        .collect();
+
+    assert_eq!(
+        claims,
+        vec![
+            (
+                String::from("wnfs://alice.example.com/public"),
+                vec![String::from("wnfs/overwrite")]
+            ),
+            (
+                String::from("wnfs://alice.example.com/public/photos"),
+                vec![String::from("wnfs/overwrite")]
+            ),
+            (
+                String::from("wnfs://alice.example.com/public/photos/vacation"),
+                vec![String::from("wnfs/append")]
+            ),
+        ]
+    );
+}
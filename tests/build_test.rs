@@ -0,0 +1,97 @@
+use cid::multihash::Code;
+use serde_json::Value;
+use std::{rc::Rc, str::FromStr};
+use ucan::Ucan;
+use ucan_fixture_generator::{
+    crypto::{ed25519_key_from_base64, rsa_key_from_base64},
+    generators::{
+        build,
+        mutate::{raw_part, sort_keys},
+        UCAN_VERSIONS,
+    },
+    identities::{Identities, KeyTypeIdentities},
+};
+
+// A harness rebuilding the token from its inputs computes the prf entries
+// from the proof tokens, so they must match
+#[tokio::test]
+async fn build_prf_lists_proof_cids() {
+    let fixtures = build::generate(
+        Rc::new(Identities::new().await),
+        &KeyTypeIdentities::new().await.unwrap(),
+        UCAN_VERSIONS[0],
+    )
+    .await
+    .unwrap();
+    let fixture = fixtures
+        .iter()
+        .find(|fixture| fixture.name() == "UCAN builds with a delegated proof")
+        .unwrap();
+    let value = serde_json::to_value(fixture).unwrap();
+
+    let cids: Vec<String> = value["inputs"]["proofs"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|proof| {
+            let proof = Ucan::from_str(proof.as_str().unwrap()).unwrap();
+            proof.to_cid(Code::Sha2_256).unwrap().to_string()
+        })
+        .collect();
+    let token = Ucan::from_str(value["outputs"]["token"].as_str().unwrap()).unwrap();
+
+    assert!(!cids.is_empty());
+    assert_eq!(token.proofs(), &Some(cids));
+}
+
+// Decoding and re-encoding an output token, as an implementation building
+// from the inputs would, must give back the same bytes
+#[tokio::test]
+async fn build_tokens_round_trip() {
+    for fixture in build::generate(
+        Rc::new(Identities::new().await),
+        &KeyTypeIdentities::new().await.unwrap(),
+        UCAN_VERSIONS[0],
+    )
+    .await
+    .unwrap()
+    {
+        let value = serde_json::to_value(&fixture).unwrap();
+        let inputs = &value["inputs"];
+        let token = value["outputs"]["token"].as_str().unwrap();
+        assert_eq!(inputs["canonicalization"], "sorted-json");
+
+        for part in ["header", "payload"] {
+            let json = raw_part(token, part).unwrap();
+            let decoded: Value = serde_json::from_str(&json).unwrap();
+            assert_eq!(serde_json::to_string(&decoded).unwrap(), json);
+        }
+
+        let issuer_key = inputs["issuer_base64_key"].as_str().unwrap();
+        let resigned = match inputs["signature_scheme"].as_str().unwrap() {
+            "Ed25519" => sort_keys(token, &ed25519_key_from_base64(issuer_key).unwrap()).await,
+            "RS256" => sort_keys(token, &rsa_key_from_base64(issuer_key).unwrap()).await,
+            scheme => panic!("Unknown signature scheme {}", scheme),
+        };
+        assert_eq!(resigned.unwrap(), token, "{}", fixture.name());
+    }
+}
+
+// The RSA fixture is built from its own options, so check it is signed
+// with the requested version like the Ed25519 fixtures
+#[tokio::test]
+async fn rsa_build_token_is_signed_with_the_requested_version() {
+    let fixture = build::generate(
+        Rc::new(Identities::new().await),
+        &KeyTypeIdentities::new().await.unwrap(),
+        "0.9.1",
+    )
+    .await
+    .unwrap()
+    .into_iter()
+    .find(|fixture| fixture.name() == "UCAN builds with an RSA signature")
+    .unwrap();
+
+    let token = Ucan::from_str(&fixture.outputs.token).unwrap();
+    assert_eq!(token.version(), "0.9.1");
+}
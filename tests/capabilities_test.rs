@@ -0,0 +1,74 @@
+use ucan::capability::CapabilitySemantics;
+use ucan_fixture_generator::capabilities::{AbilityHierarchy, HttpAction, SegmentAbility};
+
+// `ucan` grants an ability when the proof's is `>=` the claim's
+fn grants<A: PartialOrd>(proof: A, claim: A) -> bool {
+    proof >= claim
+}
+
+fn segment(ability: &str) -> SegmentAbility {
+    SegmentAbility::try_from(String::from(ability)).unwrap()
+}
+
+#[test]
+fn segment_wildcard_grants_what_it_replaces() {
+    assert!(grants(segment("crud/file/*"), segment("crud/file/read")));
+    assert!(grants(segment("crud/*"), segment("crud/file/read")));
+    assert!(grants(segment("crud/*"), segment("crud/file/*")));
+    assert!(grants(segment("crud/file/read"), segment("crud/file/read")));
+
+    assert!(!grants(segment("crud/file/read"), segment("crud/file/*")));
+    assert!(!grants(segment("crud/file/*"), segment("crud/*")));
+}
+
+#[test]
+fn segment_siblings_do_not_grant_each_other() {
+    assert!(!grants(segment("crud/file/read"), segment("crud/dir/read")));
+    assert!(!grants(segment("crud/dir/read"), segment("crud/file/read")));
+    assert!(!grants(segment("crud/file/*"), segment("crud/dir/read")));
+}
+
+fn http(ability: &str) -> HttpAction {
+    HttpAction::try_from(String::from(ability)).unwrap()
+}
+
+#[test]
+fn http_wildcard_grants_every_method() {
+    for method in ["http/GET", "http/POST", "http/PUT", "http/DELETE", "http/*"] {
+        assert!(grants(http("http/*"), http(method)), "{}", method);
+        assert!(grants(http(method), http(method)), "{}", method);
+    }
+}
+
+#[test]
+fn http_methods_do_not_grant_each_other() {
+    let methods = ["http/GET", "http/POST", "http/PUT", "http/DELETE"];
+
+    for method in methods {
+        assert!(!grants(http(method), http("http/*")), "{}", method);
+
+        for other in methods.iter().filter(|other| **other != method) {
+            assert!(!grants(http(method), http(other)), "{} {}", method, other);
+        }
+    }
+}
+
+// The order `ucan` compares by is the configured implication, not the
+// ability names
+#[test]
+fn hierarchy_grants_follow_implication() {
+    let hierarchy = AbilityHierarchy::from_json(
+        r#"{"crud/update": ["crud/create", "crud/read"], "crud/read": ["crud/list"]}"#,
+    )
+    .unwrap();
+    let semantics = hierarchy.semantics();
+    let ability = |name: &str| semantics.parse_action(name).unwrap();
+
+    assert!(grants(ability("crud/update"), ability("crud/list")));
+    assert!(grants(ability("crud/read"), ability("crud/list")));
+    assert!(grants(ability("crud/list"), ability("crud/list")));
+
+    assert!(!grants(ability("crud/list"), ability("crud/update")));
+    assert!(!grants(ability("crud/create"), ability("crud/read")));
+    assert!(!grants(ability("crud/read"), ability("crud/create")));
+}
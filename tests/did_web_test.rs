@@ -0,0 +1,35 @@
+#![cfg(feature = "did-web")]
+
+use std::{rc::Rc, str::FromStr};
+use ucan::{crypto::did::DidParser, Ucan};
+use ucan_fixture_generator::{
+    crypto::SUPPORTED_KEYS,
+    generators::{verify, UCAN_VERSIONS},
+    identities::{Identities, KeyTypeIdentities, ALICE_DID_WEB},
+};
+
+#[tokio::test]
+async fn did_web_issuer_verifies_with_resolved_key() {
+    let identities = Rc::new(Identities::new().await);
+    let fixture = verify::generate(
+        identities.clone(),
+        &KeyTypeIdentities::new().await.unwrap(),
+        UCAN_VERSIONS[0],
+    )
+    .await
+    .unwrap()
+    .into_iter()
+    .find(|fixture| fixture.name() == "UCAN is issued by a did:web DID")
+    .unwrap();
+
+    let ucan = Ucan::from_str(&fixture.inputs.token).unwrap();
+    assert_eq!(ucan.issuer(), ALICE_DID_WEB);
+
+    let resolved = &fixture.resolved_dids.unwrap()[ALICE_DID_WEB];
+    assert_eq!(resolved, &identities.alice_did);
+
+    let key = DidParser::new(SUPPORTED_KEYS).parse(resolved).unwrap();
+    key.verify(ucan.signed_data(), ucan.signature())
+        .await
+        .unwrap();
+}
@@ -0,0 +1,123 @@
+use base64::{engine::general_purpose, Engine as _};
+use serde_json::json;
+use std::{collections::BTreeSet, rc::Rc};
+use ucan_fixture_generator::{
+    crypto::ed25519_key_from_base64,
+    generators::{
+        all_fixtures,
+        errors::{error_codes, RefuteError},
+        mutate::{build_raw_token, mutate_field},
+        verify, AnyFixture, UCAN_VERSIONS,
+    },
+    identities::{Identities, KeyTypeIdentities, ALICE_BASE64_KEY},
+};
+use ucan_key_support::ed25519::Ed25519KeyMaterial;
+
+// alice's key has no private half, so the first fixture she signs fails.
+// Generation must surface that as an error naming the fixture, not panic.
+#[tokio::test]
+async fn signing_failure_is_an_error() {
+    let identities = Identities::new().await;
+    let public_only = Identities {
+        alice_key: Ed25519KeyMaterial(identities.alice_key.0, None),
+        ..identities
+    };
+
+    let err = verify::generate(
+        Rc::new(public_only),
+        &KeyTypeIdentities::new().await.unwrap(),
+        UCAN_VERSIONS[0],
+    )
+    .await
+    .expect_err("Generation signed without a private key");
+
+    assert!(
+        format!("{:#}", err).contains("Could not sign fixture"),
+        "Unexpected error: {:#}",
+        err
+    );
+}
+
+// Mutations re-sign by hand rather than through KeyMaterial, so each must
+// also report a missing private key instead of unwrapping it
+#[tokio::test]
+async fn mutating_without_private_key_is_an_error() {
+    let identities = Identities::new().await;
+    let public_only = Ed25519KeyMaterial(identities.alice_key.0, None);
+    let token = build_raw_token(
+        r#"{"alg":"EdDSA","typ":"JWT"}"#,
+        r#"{"ucv":"0.10.0"}"#,
+        identities.alice_key.clone(),
+    )
+    .unwrap();
+
+    let err = mutate_field(&token, "payload", "ucv", json!("1.0.0"), public_only)
+        .expect_err("Mutation signed without a private key");
+
+    assert_eq!(err.to_string(), "No private key; cannot sign data");
+}
+
+// An issuer key file holding fewer than 32 bytes must be rejected with an
+// error rather than a panic
+#[test]
+fn truncated_key_is_an_error() {
+    let bytes = general_purpose::STANDARD.decode(ALICE_BASE64_KEY).unwrap();
+    let truncated = general_purpose::STANDARD.encode(&bytes[..16]);
+
+    let err = ed25519_key_from_base64(&truncated).expect_err("Decoded a 16 byte key");
+
+    assert!(
+        err.to_string().contains("needs 32 bytes"),
+        "Unexpected error: {}",
+        err
+    );
+}
+
+#[test]
+fn malformed_key_is_an_error() {
+    assert!(ed25519_key_from_base64("not base64!").is_err());
+}
+
+// Refute fixtures hold errors as an enum, but serialize each as its code
+#[test]
+fn refute_errors_serialize_as_codes() {
+    for error in RefuteError::ALL {
+        assert_eq!(serde_json::to_value(error).unwrap(), error.code());
+        assert_eq!(RefuteError::from_code(error.code()), Some(*error));
+    }
+}
+
+// Every code a refute fixture emits must be in error_codes.json, or
+// consumers have nothing to map it to
+#[tokio::test]
+async fn refute_errors_are_known_codes() {
+    let known: BTreeSet<String> = error_codes()
+        .iter()
+        .map(|error| serde_json::to_value(error).unwrap()["code"].to_string())
+        .collect();
+
+    let fixtures = all_fixtures(
+        Rc::new(Identities::new().await),
+        &KeyTypeIdentities::new().await.unwrap(),
+        UCAN_VERSIONS[0],
+    )
+    .await
+    .unwrap();
+
+    for fixture in fixtures {
+        if let AnyFixture::Refute(_) = fixture {
+            let value = serde_json::to_value(&fixture).unwrap();
+            let errors = value["errors"].as_array().unwrap();
+
+            assert!(!errors.is_empty(), "{} has no errors", fixture.name());
+            for error in errors {
+                assert!(
+                    known.contains(&error.to_string()),
+                    "{}: unknown error code {}",
+                    fixture.name(),
+                    error
+                );
+            }
+        }
+    }
+}
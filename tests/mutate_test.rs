@@ -0,0 +1,153 @@
+use serde_json::{json, Value};
+use ucan_fixture_generator::{
+    generators::{
+        encode_payload,
+        mutate::{
+            build_raw_token, duplicate_payload_key, mutate_path, raw_part, replace_in_payload,
+        },
+        sign_with_options, UcanOptions,
+    },
+    identities::Identities,
+};
+
+const HEADER: &str = r#"{"alg":"EdDSA","typ":"JWT"}"#;
+const PAYLOAD: &str = r#"{"cap":{"mailto:alice@email.com":{"email/send":[{}]}}}"#;
+
+#[tokio::test]
+async fn mutates_nested_value() {
+    let identities = Identities::new().await;
+    let token = build_raw_token(HEADER, PAYLOAD, identities.alice_key.clone()).unwrap();
+
+    let mutated = mutate_path(
+        &token,
+        "payload",
+        &["cap", "mailto:alice@email.com", "email/send", "0"],
+        json!({ "templates": ["x"] }),
+        identities.alice_key,
+    )
+    .unwrap();
+
+    let payload: Value = serde_json::from_str(&raw_part(&mutated, "payload").unwrap()).unwrap();
+    assert_eq!(
+        payload,
+        json!({ "cap": { "mailto:alice@email.com": { "email/send": [{ "templates": ["x"] }] }}})
+    );
+}
+
+#[tokio::test]
+async fn missing_key_names_path() {
+    let identities = Identities::new().await;
+    let token = build_raw_token(HEADER, PAYLOAD, identities.alice_key.clone()).unwrap();
+
+    let err = mutate_path(
+        &token,
+        "payload",
+        &["cap", "mailto:bob@email.com", "email/send"],
+        json!(null),
+        identities.alice_key,
+    )
+    .unwrap_err();
+
+    assert_eq!(
+        err.to_string(),
+        "No payload field at cap.mailto:bob@email.com to mutate"
+    );
+}
+
+#[tokio::test]
+async fn duplicates_key_in_raw_payload() {
+    let identities = Identities::new().await;
+    let token = build_raw_token(HEADER, PAYLOAD, identities.alice_key.clone()).unwrap();
+
+    let duplicated = duplicate_payload_key(
+        &token,
+        "cap",
+        "mailto:alice@email.com",
+        json!({ "email/send": [] }),
+        identities.alice_key,
+    )
+    .unwrap();
+
+    assert_eq!(
+        raw_part(&duplicated, "payload").unwrap(),
+        r#"{"cap":{"mailto:alice@email.com":{"email/send":[]},"mailto:alice@email.com":{"email/send":[{}]}}}"#
+    );
+}
+
+// The key must belong to the field's own object, and the field must exist
+#[tokio::test]
+async fn duplicate_key_must_be_in_field_object() {
+    let identities = Identities::new().await;
+    let payload = r#"{"cap":{"mailto:alice@email.com":{"email/send":[{}]}},"fct":{"a":1,"b":2}}"#;
+    let token = build_raw_token(HEADER, payload, identities.alice_key.clone()).unwrap();
+
+    let nested = duplicate_payload_key(
+        &token,
+        "cap",
+        "email/send",
+        json!([]),
+        identities.alice_key.clone(),
+    )
+    .expect_err("Duplicated a key of a nested object");
+    assert_eq!(
+        nested.to_string(),
+        "No payload field cap with key email/send to duplicate"
+    );
+
+    let other_field =
+        duplicate_payload_key(&token, "cap", "b", json!(3), identities.alice_key.clone())
+            .expect_err("Duplicated a key of another field");
+    assert_eq!(
+        other_field.to_string(),
+        "No payload field cap with key b to duplicate"
+    );
+
+    let missing = duplicate_payload_key(&token, "prf", "a", json!(3), identities.alice_key)
+        .expect_err("Duplicated a key of a missing field");
+    assert_eq!(
+        missing.to_string(),
+        "No payload field prf object to duplicate a key in"
+    );
+}
+
+#[tokio::test]
+async fn replaces_raw_payload_text() {
+    let identities = Identities::new().await;
+    let token = build_raw_token(HEADER, PAYLOAD, identities.alice_key.clone()).unwrap();
+
+    let replaced =
+        replace_in_payload(&token, "[{}]", "[[[]]]", identities.alice_key.clone()).unwrap();
+    assert_eq!(
+        raw_part(&replaced, "payload").unwrap(),
+        r#"{"cap":{"mailto:alice@email.com":{"email/send":[[[]]]}}}"#
+    );
+
+    assert!(replace_in_payload(&token, "missing", "", identities.alice_key).is_err());
+}
+
+// Hand-built tokens start from encode_payload, so unmodified it must sign to
+// the same token as sign_with_options
+#[tokio::test]
+async fn encoded_payload_matches_signed_token() {
+    let identities = Identities::new().await;
+    let options = UcanOptions::for_version("0.10.0");
+
+    let payload = encode_payload(&identities.alice_key, identities.bob_did.clone(), &options)
+        .await
+        .unwrap();
+    let ucan = sign_with_options(&identities.alice_key, identities.bob_did.clone(), options)
+        .await
+        .unwrap();
+    let token = ucan.encode().unwrap();
+
+    assert_eq!(payload, raw_part(&token, "payload").unwrap());
+    assert_eq!(
+        build_raw_token(
+            &raw_part(&token, "header").unwrap(),
+            &payload,
+            identities.alice_key
+        )
+        .unwrap(),
+        token
+    );
+}
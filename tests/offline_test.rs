@@ -0,0 +1,47 @@
+use std::rc::Rc;
+use tokio::runtime;
+use ucan_fixture_generator::{
+    generators::{build, disprove, prove, refute, to_cid, verify, UCAN_VERSIONS},
+    identities::{Identities, KeyTypeIdentities},
+};
+
+// A runtime without an IO driver panics on tokio network I/O, so generating
+// every task on one shows generation makes no tokio network calls. Blocking
+// std or third-party network calls would not be caught.
+#[test]
+fn generates_fixtures_without_network_io() {
+    let runtime = runtime::Builder::new_current_thread()
+        .enable_time()
+        .build()
+        .unwrap();
+
+    runtime.block_on(async {
+        let identities = Rc::new(Identities::new().await);
+        verify::generate(
+            identities.clone(),
+            &KeyTypeIdentities::new().await.unwrap(),
+            UCAN_VERSIONS[0],
+        )
+        .await
+        .unwrap();
+        refute::generate(identities.clone(), UCAN_VERSIONS[0])
+            .await
+            .unwrap();
+        prove::generate(identities.clone(), UCAN_VERSIONS[0])
+            .await
+            .unwrap();
+        disprove::generate(identities.clone(), UCAN_VERSIONS[0])
+            .await
+            .unwrap();
+        build::generate(
+            identities.clone(),
+            &KeyTypeIdentities::new().await.unwrap(),
+            UCAN_VERSIONS[0],
+        )
+        .await
+        .unwrap();
+        to_cid::generate(identities.clone(), UCAN_VERSIONS[0])
+            .await
+            .unwrap();
+    });
+}
@@ -0,0 +1,51 @@
+use std::{rc::Rc, str::FromStr};
+use ucan::{crypto::did::DidParser, Ucan};
+use ucan_fixture_generator::{
+    crypto::SUPPORTED_KEYS,
+    generators::{verify, UCAN_VERSIONS},
+    identities::{Identities, KeyTypeIdentities},
+};
+
+#[tokio::test]
+async fn p256_tokens_verify() {
+    let mut did_parser = DidParser::new(SUPPORTED_KEYS);
+    let mut p256_fixtures = 0;
+
+    for fixture in verify::generate(
+        Rc::new(Identities::new().await),
+        &KeyTypeIdentities::new().await.unwrap(),
+        UCAN_VERSIONS[0],
+    )
+    .await
+    .unwrap()
+    {
+        if fixture.assertions.header.alg.as_deref() != Some("ES256") {
+            continue;
+        }
+        p256_fixtures += 1;
+
+        let ucan = Ucan::from_str(&fixture.inputs.token).unwrap();
+
+        ucan.check_signature(&mut did_parser)
+            .await
+            .unwrap_or_else(|err| panic!("{}: {}", fixture.name(), err));
+    }
+
+    assert!(p256_fixtures > 0);
+}
+
+// A P-256 did:key is the 0x1200 multicodec varint followed by the
+// compressed public key, base58btc encoded, so it starts with zDn
+#[tokio::test]
+async fn p256_did_has_multicodec_prefix() {
+    let identities = Identities::new_p256().await.unwrap();
+
+    for did in [&identities.alice_did, &identities.bob_did] {
+        let encoded = did.strip_prefix("did:key:z").unwrap();
+        let bytes = bs58::decode(encoded).into_vec().unwrap();
+
+        assert!(did.starts_with("did:key:zDn"), "{}", did);
+        assert_eq!(bytes[..2], [0x80, 0x24]);
+        assert_eq!(bytes.len(), 2 + 33);
+    }
+}
@@ -0,0 +1,37 @@
+use std::rc::Rc;
+use ucan_fixture_generator::{
+    generators::{
+        redact::{redact_signatures, REDACTED_SIGNATURE},
+        verify, UCAN_VERSIONS,
+    },
+    identities::{Identities, KeyTypeIdentities},
+};
+
+#[tokio::test]
+async fn redacts_every_token_signature() {
+    for fixture in verify::generate(
+        Rc::new(Identities::new().await),
+        &KeyTypeIdentities::new().await.unwrap(),
+        UCAN_VERSIONS[0],
+    )
+    .await
+    .unwrap()
+    {
+        let mut value = serde_json::to_value(&fixture).unwrap();
+        redact_signatures(&mut value);
+
+        assert_eq!(value["redacted"], true, "{}", fixture.name());
+        assert_eq!(
+            value["assertions"]["signature"],
+            REDACTED_SIGNATURE,
+            "{}",
+            fixture.name()
+        );
+
+        let proofs = value["inputs"]["proofs"].as_array().unwrap();
+        for token in std::iter::once(&value["inputs"]["token"]).chain(proofs) {
+            let signature = token.as_str().unwrap().rsplit('.').next().unwrap();
+            assert_eq!(signature, REDACTED_SIGNATURE, "{}", fixture.name());
+        }
+    }
+}
@@ -0,0 +1,119 @@
+use std::{collections::BTreeSet, rc::Rc, str::FromStr};
+use ucan::Ucan;
+use ucan_fixture_generator::{
+    crypto::ed25519_key_from_base64,
+    generators::{all_fixtures, validate::validate, verify, AnyFixture, UCAN_VERSIONS},
+    identities::{Identities, KeyTypeIdentities, ALICE_DID, ALICE_SECONDARY_BASE64_KEY},
+};
+
+#[tokio::test]
+async fn registry_covers_every_task() {
+    let fixtures = all_fixtures(
+        Rc::new(Identities::new().await),
+        &KeyTypeIdentities::new().await.unwrap(),
+        UCAN_VERSIONS[0],
+    )
+    .await
+    .unwrap();
+
+    let tasks: BTreeSet<&str> = fixtures.iter().map(AnyFixture::task).collect();
+    assert_eq!(
+        tasks,
+        BTreeSet::from(["build", "disprove", "prove", "refute", "toCID", "verify"])
+    );
+
+    for fixture in &fixtures {
+        let value = serde_json::to_value(fixture).unwrap();
+        assert_eq!(value["task"], fixture.task(), "{}", fixture.name());
+        assert_eq!(value["name"], fixture.name());
+    }
+}
+
+#[tokio::test]
+async fn registry_tokens_decode() {
+    let fixtures = all_fixtures(
+        Rc::new(Identities::new().await),
+        &KeyTypeIdentities::new().await.unwrap(),
+        UCAN_VERSIONS[0],
+    )
+    .await
+    .unwrap();
+
+    validate(&fixtures).unwrap();
+}
+
+// Build fixtures embed the issuer private key, so a caller-provided
+// issuer gets none
+// all_fixtures compares against ALICE_DID to tell whether build fixtures,
+// which embed alice's key, can be generated
+#[tokio::test]
+async fn alice_did_matches_default_key() {
+    assert_eq!(Identities::new().await.alice_did, ALICE_DID);
+}
+
+#[tokio::test]
+async fn registry_skips_build_for_caller_issuer() {
+    let issuer_key = ed25519_key_from_base64(ALICE_SECONDARY_BASE64_KEY).unwrap();
+    let identities = Rc::new(Identities::with_issuer(issuer_key).await);
+
+    let fixtures = all_fixtures(
+        identities,
+        &KeyTypeIdentities::new().await.unwrap(),
+        UCAN_VERSIONS[0],
+    )
+    .await
+    .unwrap();
+
+    assert!(!fixtures.is_empty());
+    assert!(fixtures
+        .iter()
+        .all(|fixture| !matches!(fixture, AnyFixture::Build(_))));
+}
+
+#[tokio::test]
+async fn registry_generates_with_random_identities() {
+    let identities = Identities::random().await;
+    assert_ne!(identities.alice_did, Identities::new().await.alice_did);
+
+    let fixtures = all_fixtures(
+        Rc::new(identities),
+        &KeyTypeIdentities::new().await.unwrap(),
+        UCAN_VERSIONS[0],
+    )
+    .await
+    .unwrap();
+
+    validate(&fixtures).unwrap();
+}
+
+#[tokio::test]
+async fn seeded_identities_are_reproducible() {
+    let identities = Identities::from_seed(7).await;
+
+    assert_eq!(identities.dids(), Identities::from_seed(7).await.dids());
+    assert_ne!(identities.dids(), Identities::from_seed(8).await.dids());
+    assert_ne!(identities.alice_did, Identities::new().await.alice_did);
+}
+
+// A harness can generate fixtures in memory and read them without going
+// through JSON
+#[tokio::test]
+async fn fixtures_are_readable_in_memory() {
+    let fixtures = verify::generate(
+        Rc::new(Identities::new().await),
+        &KeyTypeIdentities::new().await.unwrap(),
+        UCAN_VERSIONS[0],
+    )
+    .await
+    .unwrap();
+
+    assert!(!fixtures.is_empty());
+    for fixture in &fixtures {
+        assert_eq!(fixture.task, "verify");
+        assert!(
+            Ucan::from_str(&fixture.inputs.token).is_ok(),
+            "{}",
+            fixture.name
+        );
+    }
+}
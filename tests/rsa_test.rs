@@ -0,0 +1,40 @@
+use std::{rc::Rc, str::FromStr};
+use ucan::{crypto::did::DidParser, Ucan};
+use ucan_fixture_generator::{
+    crypto::SUPPORTED_KEYS,
+    generators::{verify, UCAN_VERSIONS},
+    identities::{Identities, KeyTypeIdentities},
+};
+
+#[tokio::test]
+async fn rsa_tokens_round_trip_and_verify() {
+    let mut did_parser = DidParser::new(SUPPORTED_KEYS);
+    let mut rsa_fixtures = 0;
+
+    for fixture in verify::generate(
+        Rc::new(Identities::new().await),
+        &KeyTypeIdentities::new().await.unwrap(),
+        UCAN_VERSIONS[0],
+    )
+    .await
+    .unwrap()
+    {
+        let value = serde_json::to_value(&fixture).unwrap();
+
+        if value["assertions"]["header"]["alg"] != "RS256" {
+            continue;
+        }
+        rsa_fixtures += 1;
+
+        let token = value["inputs"]["token"].as_str().unwrap();
+        let ucan = Ucan::from_str(token).unwrap();
+
+        assert_eq!(ucan.algorithm(), "RS256", "{}", fixture.name());
+        assert_eq!(Ucan::encode(&ucan).unwrap(), token, "{}", fixture.name());
+        ucan.check_signature(&mut did_parser)
+            .await
+            .unwrap_or_else(|err| panic!("{}: {}", fixture.name(), err));
+    }
+
+    assert!(rsa_fixtures > 0);
+}
@@ -0,0 +1,34 @@
+use serde_json::json;
+use ucan_fixture_generator::generators::schema::schemas;
+
+// exp is the one assertion serialized as null, for a token that never
+// expires. Every other optional assertion is omitted when unset.
+#[test]
+fn only_exp_assertion_is_nullable() {
+    let schema = serde_json::to_value(&schemas()["verify.json"]).unwrap();
+    let payload = &schema["definitions"]["UcanPayloadAssertions"];
+
+    assert_eq!(
+        payload["properties"]["exp"]["type"],
+        json!(["integer", "null"])
+    );
+    assert_eq!(payload["properties"]["nbf"]["type"], json!("integer"));
+    assert_eq!(payload["required"], json!(null));
+}
+
+#[test]
+fn every_task_file_has_a_schema() {
+    let files: Vec<&str> = schemas().into_keys().collect();
+
+    assert_eq!(
+        files,
+        [
+            "build.json",
+            "cid.json",
+            "disprove.json",
+            "prove.json",
+            "refute.json",
+            "verify.json"
+        ]
+    );
+}
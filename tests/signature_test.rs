@@ -0,0 +1,53 @@
+use std::{rc::Rc, str::FromStr};
+use ucan::{crypto::did::DidParser, Ucan};
+use ucan_fixture_generator::{
+    crypto::SUPPORTED_KEYS,
+    generators::{refute, verify, UCAN_VERSIONS},
+    identities::{Identities, KeyTypeIdentities},
+};
+
+// Every verify token and proof must resolve its issuer through
+// SUPPORTED_KEYS, including chains mixing key types
+#[tokio::test]
+async fn verify_tokens_and_proofs_have_valid_signatures() {
+    let mut did_parser = DidParser::new(SUPPORTED_KEYS);
+
+    for fixture in verify::generate(
+        Rc::new(Identities::new().await),
+        &KeyTypeIdentities::new().await.unwrap(),
+        UCAN_VERSIONS[0],
+    )
+    .await
+    .unwrap()
+    {
+        let value = serde_json::to_value(&fixture).unwrap();
+        let proofs = value["inputs"]["proofs"].as_array().unwrap();
+        let tokens = std::iter::once(&value["inputs"]["token"]).chain(proofs);
+
+        for token in tokens {
+            let ucan = Ucan::from_str(token.as_str().unwrap()).unwrap();
+
+            ucan.check_signature(&mut did_parser)
+                .await
+                .unwrap_or_else(|err| panic!("{}: {}", fixture.name(), err));
+        }
+    }
+}
+
+// The signature is well formed, so only checking it against the iss key
+// catches the wrong signer
+#[tokio::test]
+async fn other_key_signature_does_not_verify() {
+    let mut did_parser = DidParser::new(SUPPORTED_KEYS);
+
+    let fixture = refute::generate(Rc::new(Identities::new().await), UCAN_VERSIONS[0])
+        .await
+        .unwrap()
+        .into_iter()
+        .find(|fixture| fixture.name() == "UCAN signature is made by a key other than the iss key")
+        .unwrap();
+    let value = serde_json::to_value(&fixture).unwrap();
+    let ucan = Ucan::from_str(value["inputs"]["token"].as_str().unwrap()).unwrap();
+
+    assert!(ucan.check_signature(&mut did_parser).await.is_err());
+}
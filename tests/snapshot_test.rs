@@ -0,0 +1,83 @@
+use serde::Serialize;
+use serde_json::Value;
+use std::rc::Rc;
+use ucan_fixture_generator::{
+    generators::{build, disprove, prove, refute, to_cid, verify, UCAN_VERSIONS},
+    identities::{Identities, KeyTypeIdentities},
+};
+
+// Fields that change between runs (signatures over random nonces) are
+// replaced with a stub so snapshots only change when a generator does.
+fn stub_nondeterministic(first: Value, second: &Value) -> Value {
+    match (first, second) {
+        (Value::Array(first), Value::Array(second)) if first.len() == second.len() => Value::Array(
+            first
+                .into_iter()
+                .zip(second)
+                .map(|(first, second)| stub_nondeterministic(first, second))
+                .collect(),
+        ),
+        (Value::Object(first), Value::Object(second)) => Value::Object(
+            first
+                .into_iter()
+                .map(|(key, value)| match second.get(&key) {
+                    Some(other) => {
+                        let value = stub_nondeterministic(value, other);
+                        (key, value)
+                    }
+                    None => (key, Value::String("[nondeterministic]".into())),
+                })
+                .collect(),
+        ),
+        (first, second) if first == *second => first,
+        _ => Value::String("[nondeterministic]".into()),
+    }
+}
+
+fn snapshot_value<T: Serialize>(first: &T, second: &T) -> Value {
+    stub_nondeterministic(
+        serde_json::to_value(first).unwrap(),
+        &serde_json::to_value(second).unwrap(),
+    )
+}
+
+// Generate a task's fixtures twice and snapshot them as `snapshots/snapshot_test__<task>.snap`.
+// Tasks marked `key_types` also take the identities of every other key type.
+macro_rules! snapshot_test {
+    ($test:ident, $task:ident) => {
+        #[tokio::test]
+        async fn $test() {
+            let identities = Rc::new(Identities::new().await);
+            let fixtures = $task::generate(identities.clone(), UCAN_VERSIONS[0])
+                .await
+                .unwrap();
+            let rerun = $task::generate(identities.clone(), UCAN_VERSIONS[0])
+                .await
+                .unwrap();
+
+            insta::assert_json_snapshot!(stringify!($task), snapshot_value(&fixtures, &rerun));
+        }
+    };
+    ($test:ident, $task:ident, key_types) => {
+        #[tokio::test]
+        async fn $test() {
+            let identities = Rc::new(Identities::new().await);
+            let key_types = KeyTypeIdentities::new().await.unwrap();
+            let fixtures = $task::generate(identities.clone(), &key_types, UCAN_VERSIONS[0])
+                .await
+                .unwrap();
+            let rerun = $task::generate(identities.clone(), &key_types, UCAN_VERSIONS[0])
+                .await
+                .unwrap();
+
+            insta::assert_json_snapshot!(stringify!($task), snapshot_value(&fixtures, &rerun));
+        }
+    };
+}
+
+snapshot_test!(verify_fixtures_snapshot, verify, key_types);
+snapshot_test!(refute_fixtures_snapshot, refute);
+snapshot_test!(prove_fixtures_snapshot, prove);
+snapshot_test!(disprove_fixtures_snapshot, disprove);
+snapshot_test!(build_fixtures_snapshot, build, key_types);
+snapshot_test!(to_cid_fixtures_snapshot, to_cid);
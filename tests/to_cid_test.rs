@@ -0,0 +1,58 @@
+use std::{rc::Rc, str::FromStr};
+use ucan::Ucan;
+use ucan_fixture_generator::{
+    generators::{to_cid, UCAN_VERSIONS},
+    identities::Identities,
+};
+
+#[tokio::test]
+async fn every_cid_recomputes_from_its_token() {
+    for fixture in to_cid::generate(Rc::new(Identities::new().await), UCAN_VERSIONS[0])
+        .await
+        .unwrap()
+    {
+        let value = serde_json::to_value(&fixture).unwrap();
+        let token = value["inputs"]["token"].as_str().unwrap();
+        let hasher = value["inputs"]["hasher"].as_str().unwrap();
+        let codec = value["inputs"]["codec"].as_str().unwrap();
+        let cid = value["outputs"]["cid"].as_str().unwrap();
+
+        assert_eq!(value["outputs"]["codec"], codec, "{}", fixture.name());
+
+        let hasher_code = to_cid::hasher_code(hasher)
+            .unwrap_or_else(|| panic!("Unknown hasher {} in: {}", hasher, fixture.name()));
+        let codec_code = to_cid::codec_code(codec)
+            .unwrap_or_else(|| panic!("Unknown codec {} in: {}", codec, fixture.name()));
+
+        assert_eq!(
+            to_cid::token_cid(token, hasher_code, codec_code)
+                .unwrap()
+                .to_string(),
+            cid,
+            "CID does not recompute for: {}",
+            fixture.name()
+        );
+
+        // Raw CIDs must agree with the ucan crate
+        if codec == "raw" {
+            let ucan = Ucan::from_str(token).unwrap();
+            assert_eq!(
+                ucan.to_cid(hasher_code).unwrap().to_string(),
+                cid,
+                "Raw CID differs from ucan for: {}",
+                fixture.name()
+            );
+        }
+    }
+}
+
+// token_cid is public, so an unknown codec must be an error rather than a
+// raw CID under the wrong code
+#[test]
+fn unknown_codec_is_an_error() {
+    let hasher_code = to_cid::hasher_code("SHA2-256").unwrap();
+
+    let err = to_cid::token_cid("a.b.c", hasher_code, 0x70).expect_err("Hashed under dag-pb");
+
+    assert_eq!(err.to_string(), "Unknown codec 0x70");
+}